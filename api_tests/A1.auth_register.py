@@ -7,7 +7,7 @@ from utils import send_and_print, save_config, BASE_URL
 # Generate a unique email to ensure registration always works
 timestamp = int(time.time())
 email = f"user_{timestamp}@example.com"
-password = "password123"
+password = "Tangerine-Kite-42"
 
 print(f"--- Attempting to register: {email} ---")
 
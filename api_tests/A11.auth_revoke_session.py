@@ -0,0 +1,22 @@
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, load_config, BASE_URL
+
+access_token = load_config("access_token")
+
+if not access_token:
+    print("No access token found. Run A1.auth_register.py first.")
+elif len(sys.argv) < 2:
+    print("Usage: python A11.auth_revoke_session.py <session_id>  (see A10.auth_get_sessions.py)")
+else:
+    headers = {
+        "Authorization": f"Bearer {access_token}"
+    }
+
+    send_and_print(
+        f"{BASE_URL}/auth/sessions/{sys.argv[1]}",
+        method="DELETE",
+        headers=headers,
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json"
+    )
@@ -0,0 +1,23 @@
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, load_config, BASE_URL
+
+access_token = load_config("access_token")
+
+if not access_token:
+    print("No access token found. Run A1.auth_register.py first.")
+else:
+    headers = {
+        "Authorization": f"Bearer {access_token}"
+    }
+
+    response = send_and_print(
+        f"{BASE_URL}/auth/2fa/enroll",
+        method="POST",
+        headers=headers,
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json"
+    )
+
+    if response.status_code == 200:
+        print("\n[INFO] Add the otpauthUri to an authenticator app, then run A13.auth_2fa_confirm.py <code>.")
@@ -0,0 +1,26 @@
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, load_config, BASE_URL
+
+access_token = load_config("access_token")
+
+if not access_token:
+    print("No access token found. Run A1.auth_register.py first.")
+elif len(sys.argv) < 2:
+    print("Usage: python A13.auth_2fa_confirm.py <code>")
+else:
+    headers = {
+        "Authorization": f"Bearer {access_token}"
+    }
+
+    response = send_and_print(
+        f"{BASE_URL}/auth/2fa/confirm",
+        method="POST",
+        headers=headers,
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json",
+        body={"code": sys.argv[1]}
+    )
+
+    if response.status_code == 200:
+        print("\n[SUCCESS] Two-factor authentication enabled. Store the recovery codes somewhere safe.")
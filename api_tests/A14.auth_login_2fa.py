@@ -0,0 +1,30 @@
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, load_config, save_config, BASE_URL
+
+mfa_token = load_config("mfa_token")
+
+if not mfa_token:
+    print("No MFA token found. Run A2.auth_login.py first.")
+elif len(sys.argv) < 2:
+    print("Usage: python A14.auth_login_2fa.py <code or recovery code>")
+else:
+    payload = {
+        "mfaToken": mfa_token,
+        "code": sys.argv[1]
+    }
+
+    response = send_and_print(
+        f"{BASE_URL}/auth/login/2fa",
+        method="POST",
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json",
+        body=payload
+    )
+
+    if response.status_code == 200:
+        data = response.json()
+        save_config("access_token", data["tokens"]["accessToken"]["token"])
+        save_config("refresh_token", data["tokens"]["refreshToken"]["token"])
+        save_config("current_user_id", data["user"]["id"])
+        print("\n[SUCCESS] Logged in and tokens updated in secrets.json")
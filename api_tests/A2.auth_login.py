@@ -5,7 +5,7 @@ from utils import send_and_print, save_config, load_config, BASE_URL
 
 # Load the email registered previously, or use a default
 email = load_config("last_registered_email") or "admin@example.com"
-password = "password123"
+password = "Tangerine-Kite-42"
 
 print(f"--- Attempting to login as: {email} ---")
 
@@ -26,4 +26,7 @@ if response.status_code == 200:
     save_config("access_token", data["tokens"]["accessToken"]["token"])
     save_config("refresh_token", data["tokens"]["refreshToken"]["token"])
     save_config("current_user_id", data["user"]["id"])
-    print("\n[SUCCESS] Logged in and tokens updated in secrets.json")
\ No newline at end of file
+    print("\n[SUCCESS] Logged in and tokens updated in secrets.json")
+elif response.status_code == 202:
+    save_config("mfa_token", response.json()["mfaToken"])
+    print("\n[INFO] Two-factor authentication is enabled. Run A14.auth_login_2fa.py <code> to finish logging in.")
@@ -0,0 +1,16 @@
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, BASE_URL
+
+# The token is delivered by email, pass it as the first argument
+if len(sys.argv) < 2:
+    print("Usage: python A6.auth_verify_email.py <token>")
+else:
+    token = sys.argv[1]
+
+    send_and_print(
+        f"{BASE_URL}/auth/verify-email?token={token}",
+        method="POST",
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json"
+    )
@@ -0,0 +1,20 @@
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, load_config, BASE_URL
+
+email = load_config("last_registered_email")
+
+if not email:
+    print("No registered email found. Run A1.auth_register.py first.")
+else:
+    payload = {
+        "email": email
+    }
+
+    send_and_print(
+        f"{BASE_URL}/auth/forgot-password",
+        method="POST",
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json",
+        body=payload
+    )
@@ -0,0 +1,21 @@
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, BASE_URL
+
+# The token is delivered by email, pass it as the first argument
+if len(sys.argv) < 2:
+    print("Usage: python A8.auth_reset_password.py <token>")
+else:
+    token = sys.argv[1]
+
+    payload = {
+        "password": "Maple-Comet-58"
+    }
+
+    send_and_print(
+        f"{BASE_URL}/auth/reset-password?token={token}",
+        method="POST",
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json",
+        body=payload
+    )
@@ -0,0 +1,32 @@
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, load_config, save_config, BASE_URL
+
+access_token = load_config("access_token")
+refresh_token = load_config("refresh_token")
+
+if not access_token:
+    print("Missing access token. Login first.")
+else:
+    headers = {
+        "Authorization": f"Bearer {access_token}"
+    }
+
+    payload = {
+        "currentPassword": "Tangerine-Kite-42",
+        "newPassword": "Velvet-Otter-93",
+        # Keep this session signed in, every other session is revoked
+        "refreshToken": refresh_token
+    }
+
+    response = send_and_print(
+        f"{BASE_URL}/auth/change-password",
+        method="POST",
+        headers=headers,
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json",
+        body=payload
+    )
+
+    if response.status_code == 204:
+        print("\n[SUCCESS] Password changed. Use 'password456' to log in from now on.")
@@ -12,9 +12,11 @@ else:
         "Authorization": f"Bearer {access_token}"
     }
 
-    # Query params: page=1, limit=5
+    # Query params: page=1 (from 1), limit=5 (at most 100)
+    # Optional filters: role, isEmailVerified, createdFrom, createdTo (RFC 3339), search
+    # Sorting: sortBy=name|email|role|createdAt|updatedAt:asc|desc (default createdAt:desc)
     send_and_print(
-        f"{BASE_URL}/users?page=1&limit=5",
+        f"{BASE_URL}/users?page=1&limit=5&sortBy=name:asc",
         method="GET",
         output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json",
         headers=headers
@@ -18,8 +18,8 @@ else:
     payload = {
         "name": f"New Admin {timestamp}",
         "email": f"admin_{timestamp}@example.com",
-        "password": "password123",
-        "role": "admin"  # Can be "user" or "admin"
+        "password": "Tangerine-Kite-42",
+        "role": "admin"  # Any role name from GET /v1/roles, e.g. "user" or "admin"
     }
 
     response = send_and_print(
@@ -0,0 +1,32 @@
+import time
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, load_config, save_config, BASE_URL
+
+access_token = load_config("access_token")
+
+if not access_token:
+    print("Missing access token. Login as a user with 'roles:write' first.")
+else:
+    headers = {
+        "Authorization": f"Bearer {access_token}"
+    }
+
+    payload = {
+        "name": f"support_{int(time.time())}",
+        "description": "Support staff, can look up users",
+        "permissions": ["users:read"]
+    }
+
+    response = send_and_print(
+        f"{BASE_URL}/roles",
+        method="POST",
+        headers=headers,
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json",
+        body=payload
+    )
+
+    if response.status_code == 201:
+        save_config("target_role_id", response.json()["id"])
+        print("\n[SUCCESS] Role created and ID saved to secrets.json")
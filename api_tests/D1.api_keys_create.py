@@ -0,0 +1,33 @@
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, load_config, save_config, BASE_URL
+
+access_token = load_config("access_token")
+
+if not access_token:
+    print("No access token found. Run A1.auth_register.py first.")
+else:
+    headers = {
+        "Authorization": f"Bearer {access_token}"
+    }
+
+    payload = {
+        "name": "CI deploy",
+        "scopes": ["profile:read"],
+        "expiresInDays": 90
+    }
+
+    response = send_and_print(
+        f"{BASE_URL}/auth/api-keys",
+        method="POST",
+        headers=headers,
+        body=payload,
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json"
+    )
+
+    if response.status_code == 201:
+        data = response.json()
+        save_config("api_key", data["key"])
+        save_config("api_key_id", data["id"])
+        print("\n[INFO] API key saved. It is only shown once.")
@@ -0,0 +1,20 @@
+import sys
+import os
+sys.path.append(os.path.abspath(os.path.dirname(__file__)))
+from utils import send_and_print, load_config, BASE_URL
+
+api_key = load_config("api_key")
+
+if not api_key:
+    print("No API key found. Run D1.api_keys_create.py first.")
+else:
+    headers = {
+        "X-API-Key": api_key
+    }
+
+    send_and_print(
+        f"{BASE_URL}/users/me",
+        method="GET",
+        headers=headers,
+        output_file=f"{os.path.splitext(os.path.basename(__file__))[0]}.json"
+    )
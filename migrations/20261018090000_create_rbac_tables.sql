@@ -0,0 +1,56 @@
+-- Roles & permissions stored in the database instead of the `user_role` enum
+
+CREATE TABLE roles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(64) NOT NULL UNIQUE,
+    description TEXT NOT NULL DEFAULT '',
+    -- System roles are seeded here and cannot be deleted
+    is_system BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE permissions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL UNIQUE,
+    description TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE role_permissions (
+    role_id UUID NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+    permission_id UUID NOT NULL REFERENCES permissions(id) ON DELETE CASCADE,
+    PRIMARY KEY (role_id, permission_id)
+);
+
+-- Seed data
+INSERT INTO roles (name, description, is_system) VALUES
+    ('user', 'Default role for registered users', TRUE),
+    ('admin', 'Full access to every resource', TRUE);
+
+INSERT INTO permissions (name, description) VALUES
+    ('users:read', 'Read any user'),
+    ('users:write', 'Create, update and delete any user'),
+    ('roles:read', 'Read roles and permissions'),
+    ('roles:write', 'Create, update and delete roles');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id FROM roles r CROSS JOIN permissions p WHERE r.name = 'admin';
+
+-- Migrate existing users from the enum to a reference to `roles`
+ALTER TABLE users ALTER COLUMN role DROP DEFAULT;
+ALTER TABLE users ALTER COLUMN role TYPE VARCHAR(64) USING role::TEXT;
+ALTER TABLE users ALTER COLUMN role SET DEFAULT 'user';
+ALTER TABLE users ADD CONSTRAINT users_role_fkey FOREIGN KEY (role) REFERENCES roles(name);
+DROP TYPE user_role;
+
+-- Permission names granted to a role, used when loading users
+CREATE FUNCTION role_permission_names(role_name VARCHAR) RETURNS TEXT[] AS $$
+    SELECT COALESCE(array_agg(p.name::TEXT ORDER BY p.name), '{}')
+    FROM roles r
+    JOIN role_permissions rp ON rp.role_id = r.id
+    JOIN permissions p ON p.id = rp.permission_id
+    WHERE r.name = role_name
+$$ LANGUAGE SQL STABLE;
+
+CREATE INDEX role_permissions_permission_id_idx ON role_permissions (permission_id);
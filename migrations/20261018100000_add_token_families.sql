@@ -0,0 +1,19 @@
+-- Refresh token rotation: every login starts a family, rotated-out tokens are blacklisted
+
+ALTER TABLE tokens ADD COLUMN family_id UUID;
+
+-- Existing refresh tokens each become their own family
+UPDATE tokens SET family_id = gen_random_uuid() WHERE token_type = 'refresh';
+
+CREATE INDEX tokens_family_id_idx ON tokens (family_id);
+
+-- Audit log for security relevant events (e.g. refresh token reuse)
+CREATE TABLE security_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR(64) NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX security_events_user_id_idx ON security_events (user_id, created_at DESC);
@@ -0,0 +1,7 @@
+-- Store only a SHA-256 digest (hex) of every token instead of the token itself
+
+DROP INDEX tokens_token_unique_idx;
+ALTER TABLE tokens RENAME COLUMN token TO token_hash;
+UPDATE tokens SET token_hash = encode(sha256(convert_to(token_hash, 'UTF8')), 'hex');
+ALTER TABLE tokens ALTER COLUMN token_hash TYPE CHAR(64);
+CREATE UNIQUE INDEX tokens_token_hash_unique_idx ON tokens (token_hash);
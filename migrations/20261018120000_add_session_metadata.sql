@@ -0,0 +1,15 @@
+-- Client metadata for refresh tokens, shown in the session list
+
+ALTER TABLE tokens ADD COLUMN user_agent TEXT;
+ALTER TABLE tokens ADD COLUMN ip_address VARCHAR(45);
+
+CREATE INDEX tokens_user_id_family_id_idx ON tokens (user_id, family_id);
+
+-- Permissions to manage other users' sessions
+INSERT INTO permissions (name, description) VALUES
+    ('sessions:read', 'List the sessions of any user'),
+    ('sessions:write', 'Revoke the sessions of any user');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id FROM roles r CROSS JOIN permissions p
+WHERE r.name = 'admin' AND p.name IN ('sessions:read', 'sessions:write');
@@ -0,0 +1,13 @@
+-- Access token issued together with each refresh token, so revoking a session revokes it too
+ALTER TABLE tokens ADD COLUMN access_jti UUID;
+ALTER TABLE tokens ADD COLUMN access_expires_at TIMESTAMPTZ;
+
+-- Revoked access tokens by JWT ID. Rows are useless once the token expires and get pruned.
+CREATE TABLE revoked_access_tokens (
+    jti UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX revoked_access_tokens_expires_at_idx ON revoked_access_tokens (expires_at);
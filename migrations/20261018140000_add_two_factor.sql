@@ -0,0 +1,29 @@
+-- TOTP two-factor authentication
+
+-- Short-lived token handed out by login while the second factor is pending
+ALTER TYPE token_type ADD VALUE 'mfaPending';
+
+-- Wrong codes entered for an mfaPending token
+ALTER TABLE tokens ADD COLUMN failed_attempts INT NOT NULL DEFAULT 0;
+
+-- One row per user that started enrollment. `enabled` is set once the first code is confirmed.
+CREATE TABLE user_two_factor (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret VARCHAR(64) NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT false,
+    -- Time step of the last accepted code, so a code can't be replayed
+    last_used_step BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One-time recovery codes, stored as SHA-256 hex digests
+CREATE TABLE recovery_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash CHAR(64) NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX recovery_codes_user_id_code_hash_idx ON recovery_codes (user_id, code_hash);
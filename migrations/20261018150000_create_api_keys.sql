@@ -0,0 +1,24 @@
+-- Long-lived personal access tokens for machine clients. Only the SHA-256 hash
+-- of a key is stored; `prefix` is the start of the key, shown to tell keys apart.
+CREATE TABLE api_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    prefix VARCHAR(16) NOT NULL,
+    key_hash CHAR(64) NOT NULL UNIQUE,
+    scopes TEXT[] NOT NULL,
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX api_keys_user_id_idx ON api_keys (user_id);
+
+-- Permissions to manage other users' API keys
+INSERT INTO permissions (name, description) VALUES
+    ('api_keys:read', 'List the API keys of any user'),
+    ('api_keys:write', 'Create and revoke the API keys of any user');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id FROM roles r CROSS JOIN permissions p
+WHERE r.name = 'admin' AND p.name IN ('api_keys:read', 'api_keys:write');
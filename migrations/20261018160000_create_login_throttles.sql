@@ -0,0 +1,14 @@
+-- Failed login attempts per account (by email) and per client IP.
+-- Reaching the threshold locks the subject until `locked_until`; each lockout
+-- in a row doubles the duration.
+CREATE TABLE login_throttles (
+    kind VARCHAR(16) NOT NULL,
+    subject TEXT NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    lockouts INTEGER NOT NULL DEFAULT 0,
+    locked_until TIMESTAMPTZ,
+    last_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (kind, subject)
+);
+
+CREATE INDEX login_throttles_last_failed_at_idx ON login_throttles (last_failed_at);
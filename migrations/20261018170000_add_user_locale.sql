@@ -0,0 +1,2 @@
+-- Preferred language for responses and emails; NULL follows Accept-Language
+ALTER TABLE users ADD COLUMN locale VARCHAR(16);
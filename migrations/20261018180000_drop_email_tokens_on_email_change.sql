@@ -0,0 +1,16 @@
+-- Verification and reset links were sent to the previous address: once the
+-- email changes they must stop working. A trigger keeps this in the same
+-- transaction as the update, whichever code path changes the email.
+CREATE FUNCTION delete_email_bound_tokens() RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM tokens
+    WHERE user_id = NEW.id AND token_type IN ('verifyEmail', 'resetPassword');
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER users_email_changed
+    AFTER UPDATE OF email ON users
+    FOR EACH ROW
+    WHEN (OLD.email IS DISTINCT FROM NEW.email)
+    EXECUTE FUNCTION delete_email_bound_tokens();
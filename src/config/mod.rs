@@ -1,15 +1,221 @@
+use jsonwebtoken::Algorithm;
 use serde::Deserialize;
 use std::sync::Arc;
 use once_cell::sync::Lazy;
 
+use crate::password::PasswordHashAlgorithm;
+
 #[derive(Debug, Deserialize)]
 pub struct AppConfig {
     pub server_host: String,
     pub server_port: u16,
     pub database_url: String,
     pub jwt_secret: String,
+    /// HS256 signs with `jwt_secret`, RS256/ES256/EdDSA with the PEM keys below.
+    #[serde(default = "default_jwt_algorithm")]
+    pub jwt_algorithm: Algorithm,
+    pub jwt_key_id: Option<String>,
+    pub jwt_private_key_path: Option<String>,
+    pub jwt_public_keys_dir: Option<String>,
+    /// `iss` and `aud` of issued tokens. Tokens with other values are rejected.
+    #[serde(default = "default_jwt_issuer")]
+    pub jwt_issuer: String,
+    #[serde(default = "default_jwt_audience")]
+    pub jwt_audience: String,
     pub jwt_access_token_expires_in: String,
     pub jwt_refresh_token_expires_in: String,
+    #[serde(default = "default_jwt_verify_email_expires_in")]
+    pub jwt_verify_email_expires_in: String,
+    #[serde(default = "default_jwt_reset_password_expires_in")]
+    pub jwt_reset_password_expires_in: String,
+    #[serde(default = "default_jwt_mfa_pending_expires_in")]
+    pub jwt_mfa_pending_expires_in: String,
+    #[serde(default = "default_revoked_token_prune_interval")]
+    pub revoked_token_prune_interval: String,
+    /// Algorithm for new password hashes. Hashes of either algorithm are accepted,
+    /// and outdated ones are replaced at the next successful login.
+    #[serde(default)]
+    pub password_hash_algorithm: PasswordHashAlgorithm,
+    /// Argon2id memory in KiB, iterations and lanes.
+    #[serde(default = "default_argon2_memory_cost")]
+    pub argon2_memory_cost: u32,
+    #[serde(default = "default_argon2_time_cost")]
+    pub argon2_time_cost: u32,
+    #[serde(default = "default_argon2_parallelism")]
+    pub argon2_parallelism: u32,
+    #[serde(default = "default_bcrypt_cost")]
+    pub bcrypt_cost: u32,
+    /// Hashes computed at the same time on blocking threads. Requests wait at most
+    /// `password_hash_queue_timeout` for a slot, then get a 503.
+    #[serde(default = "default_password_hash_concurrency")]
+    pub password_hash_concurrency: usize,
+    #[serde(default = "default_password_hash_queue_timeout")]
+    pub password_hash_queue_timeout: String,
+    /// Rules for new passwords. Character classes are lowercase, uppercase, digits
+    /// and symbols; the entropy is a rough brute-force estimate in bits.
+    #[serde(default = "default_password_min_length")]
+    pub password_min_length: usize,
+    #[serde(default = "default_password_min_character_classes")]
+    pub password_min_character_classes: usize,
+    #[serde(default = "default_password_min_entropy_bits")]
+    pub password_min_entropy_bits: f64,
+    /// File of common or breached passwords to reject, one per line.
+    pub password_blocklist_path: Option<String>,
+    /// Failed logins for one account, and from one IP, before it is locked.
+    #[serde(default = "default_login_max_failed_attempts")]
+    pub login_max_failed_attempts: u32,
+    #[serde(default = "default_login_max_failed_attempts_per_ip")]
+    pub login_max_failed_attempts_per_ip: u32,
+    /// Duration of the first lockout. It doubles for every lockout in a row, up to the maximum.
+    #[serde(default = "default_login_lockout_duration")]
+    pub login_lockout_duration: String,
+    #[serde(default = "default_login_max_lockout_duration")]
+    pub login_max_lockout_duration: String,
+    /// Comma separated IPs or CIDR ranges of reverse proxies whose `X-Forwarded-For`
+    /// is believed. Without it the client IP is the address of the TCP peer.
+    #[serde(default)]
+    pub trusted_proxies: String,
+    /// Account issuer shown in authenticator apps.
+    #[serde(default = "default_totp_issuer")]
+    pub totp_issuer: String,
+
+    // Email
+    #[serde(default = "default_client_url")]
+    pub client_url: String,
+    #[serde(default)]
+    pub mail_transport: MailTransport,
+    #[serde(default = "default_mail_file_dir")]
+    pub mail_file_dir: String,
+    #[serde(default = "default_smtp_host")]
+    pub smtp_host: String,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    #[serde(default = "default_email_from")]
+    pub email_from: String,
+
+    // Localization
+    /// Language of responses and emails when neither the user nor `Accept-Language` picks one.
+    #[serde(default = "default_default_locale")]
+    pub default_locale: String,
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MailTransport {
+    #[default]
+    Smtp,
+    File,
+    Memory,
+}
+
+fn default_jwt_algorithm() -> Algorithm {
+    Algorithm::HS256
+}
+
+fn default_jwt_issuer() -> String {
+    "starter-kit-restapi-axum".to_string()
+}
+
+fn default_jwt_audience() -> String {
+    "starter-kit-restapi-axum".to_string()
+}
+
+fn default_jwt_verify_email_expires_in() -> String {
+    "10m".to_string()
+}
+
+fn default_jwt_reset_password_expires_in() -> String {
+    "10m".to_string()
+}
+
+fn default_jwt_mfa_pending_expires_in() -> String {
+    "5m".to_string()
+}
+
+fn default_argon2_memory_cost() -> u32 {
+    19 * 1024
+}
+
+fn default_argon2_time_cost() -> u32 {
+    2
+}
+
+fn default_argon2_parallelism() -> u32 {
+    1
+}
+
+fn default_bcrypt_cost() -> u32 {
+    bcrypt::DEFAULT_COST
+}
+
+fn default_password_hash_concurrency() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4)
+}
+
+fn default_password_hash_queue_timeout() -> String {
+    "1s".to_string()
+}
+
+fn default_password_min_length() -> usize {
+    8
+}
+
+fn default_password_min_character_classes() -> usize {
+    2
+}
+
+fn default_password_min_entropy_bits() -> f64 {
+    36.0
+}
+
+fn default_login_max_failed_attempts() -> u32 {
+    5
+}
+
+fn default_login_max_failed_attempts_per_ip() -> u32 {
+    20
+}
+
+fn default_login_lockout_duration() -> String {
+    "1m".to_string()
+}
+
+fn default_login_max_lockout_duration() -> String {
+    "1h".to_string()
+}
+
+fn default_totp_issuer() -> String {
+    "Starter Kit".to_string()
+}
+
+fn default_revoked_token_prune_interval() -> String {
+    "1h".to_string()
+}
+
+fn default_client_url() -> String {
+    "http://localhost:3000".to_string()
+}
+
+fn default_mail_file_dir() -> String {
+    "emails".to_string()
+}
+
+fn default_smtp_host() -> String {
+    "localhost".to_string()
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_email_from() -> String {
+    "noreply@example.com".to_string()
+}
+
+fn default_default_locale() -> String {
+    "en".to_string()
 }
 
 pub static CONFIG: Lazy<Arc<AppConfig>> = Lazy::new(|| {
@@ -23,4 +229,4 @@ pub static CONFIG: Lazy<Arc<AppConfig>> = Lazy::new(|| {
     let app_config: AppConfig = config.try_deserialize().expect("Failed to deserialize config");
 
     Arc::new(app_config)
-});
\ No newline at end of file
+});
@@ -0,0 +1,41 @@
+use serde::Serialize;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+/// Start of every API key, so the `auth` middleware can tell keys from JWTs.
+pub const API_KEY_PREFIX: &str = "pat_";
+
+/// A personal access token. The key itself is only returned when it is created.
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[schema(example = "CI deploy")]
+    pub name: String,
+    /// First characters of the key, to recognize it.
+    #[schema(example = "pat_3kTq9Zb1")]
+    pub prefix: String,
+    #[schema(example = json!(["profile:read"]))]
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+}
+
+/// Returned once when a key is created. The key can't be retrieved again.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiKey {
+    #[schema(example = "pat_3kTq9Zb1Xr7LmN2pQ8sVw4Yc6Hd0Jf5GtAeUoKiB")]
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+}
@@ -1,2 +1,5 @@
 pub mod user_model;
-pub mod token_model;
\ No newline at end of file
+pub mod token_model;
+pub mod role_model;
+pub mod session_model;pub mod two_factor_model;
+pub mod api_key_model;
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+/// Role given to newly registered users.
+pub const DEFAULT_ROLE: &str = "user";
+
+/// Permission names checked by the application. They are seeded by migrations.
+pub mod permissions {
+    pub const USERS_READ: &str = "users:read";
+    pub const USERS_WRITE: &str = "users:write";
+    pub const ROLES_READ: &str = "roles:read";
+    pub const ROLES_WRITE: &str = "roles:write";
+    pub const SESSIONS_READ: &str = "sessions:read";
+    pub const SESSIONS_WRITE: &str = "sessions:write";
+    pub const API_KEYS_READ: &str = "api_keys:read";
+    pub const API_KEYS_WRITE: &str = "api_keys:write";
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Role {
+    pub id: Uuid,
+    #[schema(example = "support")]
+    pub name: String,
+    pub description: String,
+    pub is_system: bool,
+    #[schema(example = json!(["users:read"]))]
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    pub id: Uuid,
+    #[schema(example = "users:read")]
+    pub name: String,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+}
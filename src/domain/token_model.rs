@@ -16,12 +16,58 @@ pub struct TokenResponse {
     pub refresh_token: TokenDetails,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Scopes an access token can carry in its space separated `scope` claim.
+/// A route requiring a scope is still subject to the user's permissions.
+pub mod scopes {
+    /// The own account: profile, password and sessions.
+    pub const PROFILE_READ: &str = "profile:read";
+    pub const PROFILE_WRITE: &str = "profile:write";
+    pub const USERS_READ: &str = "users:read";
+    pub const USERS_WRITE: &str = "users:write";
+    pub const ROLES_READ: &str = "roles:read";
+    pub const ROLES_WRITE: &str = "roles:write";
+
+    /// Scopes of the access tokens issued by login, register and refresh.
+    pub const ALL: &[&str] = &[
+        PROFILE_READ, PROFILE_WRITE, USERS_READ, USERS_WRITE, ROLES_READ, ROLES_WRITE,
+    ];
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenClaims {
     pub sub: Uuid,
+    pub iss: String,
+    pub aud: String,
+    /// Unique token id, so two tokens issued in the same second never collide.
+    /// Access tokens are revoked by adding it to the denylist.
+    pub jti: Uuid,
     pub iat: usize,
     pub exp: usize,
     pub token_type: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub scope: String,
+}
+
+/// How the current request authenticated. Inserted by the `auth` middleware
+/// next to the `User`, for access tokens and API keys alike.
+#[derive(Debug, Clone)]
+pub struct AuthContext {
+    pub scopes: Vec<String>,
+    /// Set when the request used an API key instead of an access token.
+    pub api_key_id: Option<Uuid>,
+}
+
+impl AuthContext {
+    pub fn from_claims(claims: &TokenClaims) -> Self {
+        Self {
+            scopes: claims.scope.split_whitespace().map(str::to_string).collect(),
+            api_key_id: None,
+        }
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
 }
 
 #[derive(Debug, sqlx::Type, Clone, PartialEq)]
@@ -30,4 +76,17 @@ pub enum TokenType {
     Refresh,
     ResetPassword,
     VerifyEmail,
-}
\ No newline at end of file
+    MfaPending,
+}
+
+impl TokenType {
+    /// Value used for the `token_type` claim of JWTs of this type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenType::Refresh => "refresh",
+            TokenType::ResetPassword => "resetPassword",
+            TokenType::VerifyEmail => "verifyEmail",
+            TokenType::MfaPending => "mfaPending",
+        }
+    }
+}
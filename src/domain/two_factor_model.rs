@@ -0,0 +1,35 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{token_model::TokenResponse, user_model::FilteredUser};
+
+/// Returned when 2FA enrollment starts. The secret is shown once for manual entry.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorEnrollment {
+    #[schema(example = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP")]
+    pub secret: String,
+    #[schema(example = "otpauth://totp/Starter%20Kit:user@example.com?secret=JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP&issuer=Starter%20Kit")]
+    pub otpauth_uri: String,
+}
+
+/// One-time recovery codes, only ever shown when they are generated.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodes {
+    #[schema(example = json!(["abcde-23456", "fghjk-789ab"]))]
+    pub recovery_codes: Vec<String>,
+}
+
+/// Handed out by login when the user has 2FA enabled, exchanged at `/v1/auth/login/2fa`.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaChallenge {
+    pub mfa_token: String,
+    pub expires_in: i64,
+}
+
+pub enum LoginOutcome {
+    Authenticated(FilteredUser, TokenResponse),
+    MfaRequired(MfaChallenge),
+}
@@ -12,28 +12,21 @@ pub struct User {
     pub email: String,
     #[serde(skip_serializing)]
     pub password: String,
-    #[sqlx(try_from = "String")]
-    pub role: Role,
+    /// Name of the user's role in the `roles` table.
+    pub role: String,
+    /// Permissions granted by `role`, loaded together with the user.
+    #[serde(skip)]
+    pub permissions: Vec<String>,
     pub is_email_verified: bool,
+    /// Preferred language (`en`, `id`); `None` follows `Accept-Language`.
+    pub locale: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type, Clone, ToSchema, PartialEq, PartialOrd, Eq, Ord)]
-#[sqlx(type_name = "user_role", rename_all = "lowercase")]
-pub enum Role {
-    User,
-    Admin,
-}
-
-impl TryFrom<String> for Role {
-    type Error = &'static str;
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        match value.as_str() {
-            "user" => Ok(Role::User),
-            "admin" => Ok(Role::Admin),
-            _ => Err("Invalid role"),
-        }
+impl User {
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|p| p == permission)
     }
 }
 
@@ -43,8 +36,12 @@ pub struct FilteredUser {
     pub id: Uuid,
     pub name: String,
     pub email: String,
-    pub role: Role,
+    #[schema(example = "user")]
+    pub role: String,
     pub is_email_verified: bool,
+    /// Preferred language for messages and emails.
+    #[schema(example = "id")]
+    pub locale: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -57,8 +54,9 @@ impl From<User> for FilteredUser {
             email: user.email,
             role: user.role,
             is_email_verified: user.is_email_verified,
+            locale: user.locale,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
     }
-}
\ No newline at end of file
+}
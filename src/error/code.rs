@@ -0,0 +1,87 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+macro_rules! error_codes {
+    ($($(#[$meta:meta])* $variant:ident => $code:literal,)*) => {
+        /// Stable, machine-readable error codes. Clients should match on these
+        /// rather than on the human-readable `detail`, which may change.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+        #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+        pub enum ErrorCode {
+            $(
+                $(#[$meta])*
+                $variant,
+            )*
+        }
+
+        impl ErrorCode {
+            /// Same string as the serialized form.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code,)*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    // Generic
+    InternalError => "INTERNAL_ERROR",
+    ServerBusy => "SERVER_BUSY",
+    RouteNotFound => "ROUTE_NOT_FOUND",
+    MethodNotAllowed => "METHOD_NOT_ALLOWED",
+    ValidationFailed => "VALIDATION_FAILED",
+    MalformedBody => "MALFORMED_BODY",
+    UnsupportedMediaType => "UNSUPPORTED_MEDIA_TYPE",
+    PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+    InvalidPathParameter => "INVALID_PATH_PARAMETER",
+    InvalidQueryParameter => "INVALID_QUERY_PARAMETER",
+    DuplicateResource => "DUPLICATE_RESOURCE",
+    RelatedResourceNotFound => "RELATED_RESOURCE_NOT_FOUND",
+    RequiredFieldMissing => "REQUIRED_FIELD_MISSING",
+    DatabaseError => "DATABASE_ERROR",
+    DatabaseUnavailable => "DATABASE_UNAVAILABLE",
+    EmailDeliveryFailed => "EMAIL_DELIVERY_FAILED",
+
+    // Authentication
+    AuthenticationRequired => "AUTHENTICATION_REQUIRED",
+    InvalidCredentials => "INVALID_CREDENTIALS",
+    LoginLocked => "LOGIN_LOCKED",
+    TokenInvalid => "TOKEN_INVALID",
+    TokenExpired => "TOKEN_EXPIRED",
+    TokenRevoked => "TOKEN_REVOKED",
+    TokenNotFound => "TOKEN_NOT_FOUND",
+    ApiKeyInvalid => "API_KEY_INVALID",
+    ApiKeyExpired => "API_KEY_EXPIRED",
+    ApiKeyNotFound => "API_KEY_NOT_FOUND",
+    MfaChallengeInvalid => "MFA_CHALLENGE_INVALID",
+    MfaChallengeExhausted => "MFA_CHALLENGE_EXHAUSTED",
+    TwoFactorCodeInvalid => "TWO_FACTOR_CODE_INVALID",
+    TwoFactorAlreadyEnabled => "TWO_FACTOR_ALREADY_ENABLED",
+    TwoFactorNotEnabled => "TWO_FACTOR_NOT_ENABLED",
+    TwoFactorNotEnrolled => "TWO_FACTOR_NOT_ENROLLED",
+    CurrentPasswordRequired => "CURRENT_PASSWORD_REQUIRED",
+    CurrentPasswordIncorrect => "CURRENT_PASSWORD_INCORRECT",
+    EmailVerificationFailed => "EMAIL_VERIFICATION_FAILED",
+    EmailAlreadyVerified => "EMAIL_ALREADY_VERIFIED",
+    PasswordResetFailed => "PASSWORD_RESET_FAILED",
+    SessionNotFound => "SESSION_NOT_FOUND",
+
+    // Authorization
+    PermissionDenied => "PERMISSION_DENIED",
+    ScopeMissing => "SCOPE_MISSING",
+    ScopesRequired => "SCOPES_REQUIRED",
+    UnknownScope => "UNKNOWN_SCOPE",
+
+    // Users and roles
+    UserNotFound => "USER_NOT_FOUND",
+    EmailTaken => "EMAIL_TAKEN",
+    EmailInvalid => "EMAIL_INVALID",
+    RoleNotFound => "ROLE_NOT_FOUND",
+    RoleExists => "ROLE_EXISTS",
+    RoleInUse => "ROLE_IN_USE",
+    RoleProtected => "ROLE_PROTECTED",
+    OwnRoleChange => "OWN_ROLE_CHANGE",
+    UnknownPermission => "UNKNOWN_PERMISSION",
+}
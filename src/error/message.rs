@@ -0,0 +1,45 @@
+use std::fmt;
+
+use super::ErrorCode;
+use crate::i18n::{self, Locale};
+
+/// The message of an error: its code, which keys the `errors` entry of the
+/// message catalog, and the values for the entry's placeholders.
+#[derive(Debug, Clone)]
+pub struct ErrorMessage {
+    pub code: ErrorCode,
+    args: Vec<(&'static str, String)>,
+}
+
+impl ErrorMessage {
+    pub fn arg(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    pub fn render(&self, locale: Locale) -> String {
+        let args: Vec<(&str, &str)> = self.args.iter().map(|(name, value)| (*name, value.as_str())).collect();
+        i18n::lookup(locale, &format!("errors.{}", self.code.as_str()), &args)
+            .unwrap_or_else(|| self.code.as_str().to_string())
+    }
+}
+
+impl From<ErrorCode> for ErrorMessage {
+    fn from(code: ErrorCode) -> Self {
+        Self { code, args: Vec::new() }
+    }
+}
+
+impl ErrorCode {
+    /// Starts a message with a value for the `{name}` placeholder.
+    pub fn with(self, name: &'static str, value: impl ToString) -> ErrorMessage {
+        ErrorMessage::from(self).arg(name, value)
+    }
+}
+
+/// The English message, as logged.
+impl fmt::Display for ErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(Locale::En))
+    }
+}
@@ -1,29 +1,57 @@
 use axum::{
-    http::StatusCode,
+    extract::rejection::{ExtensionRejection, JsonRejection, PathRejection, QueryRejection},
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use jsonwebtoken::errors::ErrorKind as JwtErrorKind;
 use thiserror::Error;
 use serde::Serialize;
 use utoipa::ToSchema;
 
+use crate::i18n::{self, Locale};
+
+mod code;
+mod message;
+mod validation;
+
+pub use code::ErrorCode;
+pub use message::ErrorMessage;
+pub use validation::FieldError;
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Internal Server Error")]
     InternalServerError,
 
     #[error("Not Found: {0}")]
-    NotFound(String),
+    NotFound(ErrorMessage),
 
     #[error("Bad Request: {0}")]
-    BadRequest(String),
+    BadRequest(ErrorMessage),
 
     #[error("Unauthorized: {0}")]
-    Unauthorized(String),
+    Unauthorized(ErrorMessage),
     
     #[error("Forbidden: {0}")]
-    Forbidden(String),
+    Forbidden(ErrorMessage),
+
+    #[error("Method Not Allowed")]
+    MethodNotAllowed,
+
+    /// An axum extractor refused the request; keeps the status axum chose.
+    #[error("Rejected: {message}")]
+    Rejection { status: StatusCode, message: ErrorMessage },
+
+    /// Answered with a `Retry-After` header of `retry_after` seconds.
+    #[error("Too Many Requests: {code:?}")]
+    TooManyRequests { code: ErrorCode, retry_after: u64 },
+
+    /// Answered with a `Retry-After` header of `retry_after` seconds.
+    #[error("Service Unavailable: {code:?}")]
+    ServiceUnavailable { code: ErrorCode, retry_after: u64 },
 
     #[error(transparent)]
     SqlxError(#[from] sqlx::Error),
@@ -36,75 +64,203 @@ pub enum AppError {
 
     #[error(transparent)]
     BcryptError(#[from] bcrypt::BcryptError),
+
+    #[error(transparent)]
+    EmailError(#[from] lettre::transport::smtp::Error),
+}
+
+/// RFC 7807 problem details, served as `application/problem+json`.
+///
+/// `instance` and `request_id` are filled in by the `request_id` middleware.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemDetails {
+    /// URI identifying the problem type, derived from `code`.
+    #[serde(rename = "type")]
+    #[schema(example = "urn:problem-type:email-taken")]
+    pub problem_type: String,
+    /// Reason phrase of the HTTP status.
+    #[schema(example = "Bad Request")]
+    pub title: String,
+    #[schema(example = 400)]
+    pub status: u16,
+    #[schema(example = "Email already taken")]
+    pub detail: String,
+    /// Path of the request that failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "/v1/auth/register")]
+    pub instance: Option<String>,
+    pub code: ErrorCode,
+    /// Every failed rule when `code` is `VALIDATION_FAILED`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<FieldError>>,
+    /// Same value as the `X-Request-Id` response header.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Source of `detail`, kept to render it again in the response's language.
+    #[serde(skip)]
+    message: ErrorMessage,
 }
 
-#[derive(Serialize, ToSchema)]
-pub struct ErrorResponse {
-    pub code: u16,
-    pub message: String,
+impl ProblemDetails {
+    pub fn new(status: StatusCode, message: ErrorMessage) -> Self {
+        let code = message.code;
+        Self {
+            problem_type: format!("urn:problem-type:{}", code.as_str().to_lowercase().replace('_', "-")),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: message.to_string(),
+            instance: None,
+            code,
+            errors: None,
+            request_id: None,
+            message,
+        }
+    }
+
+    /// Renders `title`, `detail` and field error messages in `locale`.
+    pub fn localize(&mut self, locale: Locale) {
+        if let Some(title) = i18n::lookup(locale, &format!("titles.{}", self.status), &[]) {
+            self.title = title;
+        }
+
+        match &mut self.errors {
+            Some(errors) => {
+                for error in errors.iter_mut() {
+                    error.localize(locale);
+                }
+                self.detail = errors.iter().map(|e| e.message.as_str()).collect::<Vec<_>>().join("; ");
+            }
+            None => self.detail = self.message.render(locale),
+        }
+    }
+}
+
+impl IntoResponse for ProblemDetails {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, Json(self.clone())).into_response();
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+        // Lets middleware complete the body with request-scoped fields
+        response.extensions_mut().insert(self);
+        response
+    }
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            AppError::InternalServerError => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Internal Server Error".to_string(),
-            ),
-            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
-            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
-            AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
-            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+        let retry_after = match &self {
+            AppError::TooManyRequests { retry_after, .. }
+            | AppError::ServiceUnavailable { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        };
+
+        let mut field_errors = None;
+        let (status, message): (StatusCode, ErrorMessage) = match self {
+            AppError::InternalServerError => (StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::InternalError.into()),
+            AppError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            AppError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            AppError::Unauthorized(message) => (StatusCode::UNAUTHORIZED, message),
+            AppError::Forbidden(message) => (StatusCode::FORBIDDEN, message),
+            AppError::MethodNotAllowed => (StatusCode::METHOD_NOT_ALLOWED, ErrorCode::MethodNotAllowed.into()),
+            AppError::Rejection { status, message } => {
+                if status.is_server_error() {
+                    tracing::error!("Extractor rejection: {}", message);
+                    (status, ErrorCode::InternalError.into())
+                } else {
+                    (status, message)
+                }
+            }
+            AppError::TooManyRequests { code, .. } => (StatusCode::TOO_MANY_REQUESTS, code.into()),
+            AppError::ServiceUnavailable { code, .. } => (StatusCode::SERVICE_UNAVAILABLE, code.into()),
             AppError::SqlxError(err) => {
                 tracing::error!("SQLx error: {:?}", err);
                 match &err {
                     sqlx::Error::Database(db_err) => {
                         match db_err.kind() {
-                            sqlx::error::ErrorKind::UniqueViolation => (
-                                StatusCode::CONFLICT,
-                                "The data you entered already exists.".to_string(),
-                            ),
-                            sqlx::error::ErrorKind::ForeignKeyViolation => (
-                                StatusCode::BAD_REQUEST,
-                                "No related data found.".to_string(),
-                            ),
-                            sqlx::error::ErrorKind::NotNullViolation => (
-                                StatusCode::BAD_REQUEST,
-                                "There are required fields that are empty.".to_string(),
-                            ),
-                            _ => (
-                                StatusCode::INTERNAL_SERVER_ERROR,
-                                "An error occurred in the database.".to_string(),
-                            ),
+                            sqlx::error::ErrorKind::UniqueViolation => (StatusCode::CONFLICT, ErrorCode::DuplicateResource.into()),
+                            sqlx::error::ErrorKind::ForeignKeyViolation => (StatusCode::BAD_REQUEST, ErrorCode::RelatedResourceNotFound.into()),
+                            sqlx::error::ErrorKind::NotNullViolation => (StatusCode::BAD_REQUEST, ErrorCode::RequiredFieldMissing.into()),
+                            _ => (StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::DatabaseError.into()),
                         }
                     }
-                    _ => (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        "There is a problem connecting to the database.".to_string(),
-                    ),
+                    _ => (StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::DatabaseUnavailable.into()),
                 }
             }
             AppError::JwtError(err) => {
                 tracing::error!("JWT error: {:?}", err);
-                (
-                    StatusCode::UNAUTHORIZED,
-                    "Invalid or expired token".to_string(),
-                )
+                match err.kind() {
+                    JwtErrorKind::ExpiredSignature => (StatusCode::UNAUTHORIZED, ErrorCode::TokenExpired.into()),
+                    _ => (StatusCode::UNAUTHORIZED, ErrorCode::TokenInvalid.into()),
+                }
             }
             AppError::ValidationErrors(err) => {
-                 let messages = err.field_errors().into_iter().map(|(_, errors)| {
-                    errors.iter().map(|e| e.message.as_ref().unwrap().to_string()).collect::<Vec<_>>().join(", ")
-                }).collect::<Vec<_>>().join("; ");
-                (StatusCode::BAD_REQUEST, messages)
+                field_errors = Some(validation::field_errors(&err));
+                (StatusCode::BAD_REQUEST, ErrorCode::ValidationFailed.into())
+            }
+            AppError::BcryptError(err) => {
+                tracing::error!("Bcrypt error: {:?}", err);
+                (StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::InternalError.into())
             }
-            AppError::BcryptError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Password hashing error".to_string()),
+            AppError::EmailError(err) => {
+                tracing::error!("Email error: {:?}", err);
+                (StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::EmailDeliveryFailed.into())
+            }
+        };
+
+        let mut problem = ProblemDetails::new(status, message);
+        if let Some(errors) = field_errors {
+            problem.detail = errors.iter().map(|e| e.message.as_str()).collect::<Vec<_>>().join("; ");
+            problem.errors = Some(errors);
+        }
+        let mut response = problem.into_response();
+        if let Some(seconds) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
+    }
+}
+
+/// What axum says is wrong, without its generic lead-in such as
+/// `Failed to deserialize query string: `, which the catalog words itself.
+fn rejection_reason(body_text: &str) -> &str {
+    body_text.split_once(": ").map_or(body_text, |(_, reason)| reason)
+}
+
+impl From<JsonRejection> for AppError {
+    fn from(rejection: JsonRejection) -> Self {
+        let message = match &rejection {
+            JsonRejection::MissingJsonContentType(_) => ErrorCode::UnsupportedMediaType.into(),
+            _ if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE => ErrorCode::PayloadTooLarge.into(),
+            _ => ErrorCode::MalformedBody.with("reason", rejection_reason(&rejection.body_text())),
         };
+        AppError::Rejection { status: rejection.status(), message }
+    }
+}
 
-        let body = Json(ErrorResponse {
-            code: status.as_u16(),
-            message: error_message,
-        });
+impl From<PathRejection> for AppError {
+    fn from(rejection: PathRejection) -> Self {
+        AppError::Rejection {
+            status: rejection.status(),
+            message: ErrorCode::InvalidPathParameter.with("reason", rejection_reason(&rejection.body_text())),
+        }
+    }
+}
 
-        (status, body).into_response()
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        AppError::Rejection {
+            status: rejection.status(),
+            message: ErrorCode::InvalidQueryParameter.with("reason", rejection_reason(&rejection.body_text())),
+        }
     }
-}
\ No newline at end of file
+}
+
+impl From<ExtensionRejection> for AppError {
+    fn from(rejection: ExtensionRejection) -> Self {
+        tracing::error!("Extension rejection: {}", rejection.body_text());
+        AppError::Rejection { status: rejection.status(), message: ErrorCode::InternalError.into() }
+    }
+}
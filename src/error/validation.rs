@@ -0,0 +1,125 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::i18n::{self, Locale};
+
+/// One failed validation rule, as reported in the `errors` of a problem.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FieldError {
+    /// JSON path of the field, e.g. `newPassword`, `address.city` or `items[0].name`;
+    /// empty for rules on the whole body.
+    #[schema(example = "email")]
+    pub field: String,
+    /// Validator code, e.g. `email`, `length` or `password_too_short`.
+    #[schema(example = "email")]
+    pub code: String,
+    #[schema(example = "Email is invalid")]
+    pub message: String,
+    /// Arguments of the rule, e.g. `{"min": 8}`. The submitted value is never echoed back.
+    #[schema(value_type = Object)]
+    pub params: HashMap<String, serde_json::Value>,
+}
+
+impl FieldError {
+    /// Renders `message` in `locale`, preferring field-specific wording over the
+    /// generic one for `code`. A rule the catalog doesn't know keeps its own
+    /// message, or gets the generic fallback if it has none.
+    pub fn localize(&mut self, locale: Locale) {
+        let params: Vec<(&str, String)> = self
+            .params
+            .iter()
+            .map(|(name, value)| {
+                let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                (name.as_str(), value)
+            })
+            .collect();
+        let args: Vec<(&str, &str)> = params.iter().map(|(name, value)| (*name, value.as_str())).collect();
+        // List indices don't change the wording: `items[0].name` uses `items.name`
+        let field: String = self.field.split('[').enumerate()
+            .map(|(i, part)| if i == 0 { part } else { part.split_once(']').map_or(part, |(_, rest)| rest) })
+            .collect();
+        let mut keys = vec![
+            format!("validation.fields.{}.{}", field, self.code),
+            format!("validation.rules.{}", self.code),
+        ];
+        if self.message.is_empty() {
+            keys.push("validation.rules.fallback".to_string());
+        }
+        if let Some(message) = keys.iter().find_map(|key| i18n::lookup(locale, key, &args)) {
+            self.message = message;
+        }
+    }
+}
+
+/// Flattens nested struct and list errors into one list, ordered by field path.
+pub fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut flattened = Vec::new();
+    collect(errors, "", &mut flattened);
+    flattened.sort_by(|a, b| a.field.cmp(&b.field));
+    flattened
+}
+
+fn collect(errors: &ValidationErrors, prefix: &str, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        // Struct-level rules are reported under `__all__` and belong to the struct itself
+        let path = match *field {
+            "__all__" => prefix.to_string(),
+            field => join_path(prefix, &to_camel_case(field)),
+        };
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                out.extend(errors.iter().map(|error| field_error(&path, error)));
+            }
+            ValidationErrorsKind::Struct(errors) => collect(errors, &path, out),
+            ValidationErrorsKind::List(items) => collect_list(items, &path, out),
+        }
+    }
+}
+
+fn collect_list(items: &BTreeMap<usize, Box<ValidationErrors>>, path: &str, out: &mut Vec<FieldError>) {
+    for (index, errors) in items {
+        collect(errors, &format!("{}[{}]", path, index), out);
+    }
+}
+
+fn field_error(path: &str, error: &ValidationError) -> FieldError {
+    let params = error
+        .params
+        .iter()
+        .filter(|(name, _)| name.as_ref() != "value")
+        .map(|(name, value)| (name.to_string(), value.clone()))
+        .collect();
+    let message = error.message.as_deref().unwrap_or_default().to_string();
+
+    let mut field_error = FieldError { field: path.to_string(), code: error.code.to_string(), message, params };
+    field_error.localize(Locale::En);
+    field_error
+}
+
+fn join_path(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", prefix, field)
+    }
+}
+
+/// Request bodies use camelCase while validator reports Rust field names.
+fn to_camel_case(field: &str) -> String {
+    let mut camel = String::with_capacity(field.len());
+    let mut upper_next = false;
+    for c in field.chars() {
+        if c == '_' {
+            upper_next = !camel.is_empty();
+        } else if upper_next {
+            camel.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
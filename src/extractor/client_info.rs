@@ -0,0 +1,159 @@
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts, HeaderMap},
+};
+use ipnet::IpNet;
+use once_cell::sync::Lazy;
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
+
+use crate::{config::CONFIG, domain::session_model::ClientInfo};
+
+/// `TRUSTED_PROXIES`, parsed once.
+static TRUSTED_PROXIES: Lazy<Vec<IpNet>> = Lazy::new(|| parse_trusted_proxies(&CONFIG.trusted_proxies));
+
+/// Comma-separated IPs and CIDR ranges. Entries that are neither are skipped.
+fn parse_trusted_proxies(value: &str) -> Vec<IpNet> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let net = entry.parse::<IpNet>().ok().or_else(|| entry.parse::<IpAddr>().ok().map(IpNet::from));
+            if net.is_none() {
+                tracing::warn!("Ignoring invalid TRUSTED_PROXIES entry {:?}", entry);
+            }
+            net
+        })
+        .collect()
+}
+
+/// The client address: the TCP peer, unless the peer is a trusted proxy. Then
+/// `X-Forwarded-For` is walked from the right, as every proxy appends the
+/// address it received the request from, and the first hop that isn't a
+/// trusted proxy is the client. Entries left of it may be made up by the
+/// client and are never used.
+fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpNet]) -> IpAddr {
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    let mut client = peer.to_canonical();
+    if !is_trusted(&client) {
+        return client;
+    }
+
+    for value in headers.get_all("X-Forwarded-For").iter().rev() {
+        let Ok(value) = value.to_str() else { return client };
+        for hop in value.rsplit(',') {
+            // A malformed hop means the header can't be trusted beyond this point
+            let Ok(hop) = hop.trim().parse::<IpAddr>() else { return client };
+            client = hop.to_canonical();
+            if !is_trusted(&client) {
+                return client;
+            }
+        }
+    }
+    client
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(512).collect());
+
+        let ip_address = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| client_ip(addr.ip(), &parts.headers, &TRUSTED_PROXIES).to_string());
+
+        Ok(ClientInfo { user_agent, ip_address })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(forwarded_for: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in forwarded_for {
+            headers.append("X-Forwarded-For", value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn proxies() -> Vec<IpNet> {
+        parse_trusted_proxies("10.0.0.0/8, 192.168.1.1")
+    }
+
+    #[test]
+    fn parses_ips_and_ranges_and_skips_invalid_entries() {
+        let proxies = parse_trusted_proxies(" 10.0.0.0/8,,192.168.1.1, not-an-ip, ::1 ");
+        let expected: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap(), "192.168.1.1/32".parse().unwrap(), "::1/128".parse().unwrap()];
+        assert_eq!(proxies, expected);
+        assert!(parse_trusted_proxies("").is_empty());
+    }
+
+    #[test]
+    fn untrusted_peer_is_the_client_whatever_the_header_says() {
+        let forwarded = headers(&["203.0.113.7"]);
+        assert_eq!(client_ip(ip("198.51.100.1"), &forwarded, &proxies()), ip("198.51.100.1"));
+        assert_eq!(client_ip(ip("10.0.0.1"), &forwarded, &[]), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn trusted_peer_without_the_header_is_the_client() {
+        assert_eq!(client_ip(ip("10.0.0.1"), &headers(&[]), &proxies()), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn walks_trusted_hops_from_the_right() {
+        let forwarded = headers(&["203.0.113.7, 192.168.1.1, 10.1.2.3"]);
+        assert_eq!(client_ip(ip("10.0.0.1"), &forwarded, &proxies()), ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn stops_at_the_first_untrusted_hop() {
+        // The left-most entry is whatever the client sent and is never used
+        let forwarded = headers(&["1.1.1.1, 198.51.100.9, 10.1.2.3"]);
+        assert_eq!(client_ip(ip("10.0.0.1"), &forwarded, &proxies()), ip("198.51.100.9"));
+    }
+
+    #[test]
+    fn walks_repeated_headers_last_first() {
+        let forwarded = headers(&["1.1.1.1", "198.51.100.9", "10.1.2.3"]);
+        assert_eq!(client_ip(ip("10.0.0.1"), &forwarded, &proxies()), ip("198.51.100.9"));
+    }
+
+    #[test]
+    fn malformed_hop_ends_the_walk() {
+        let forwarded = headers(&["203.0.113.7, unknown, 10.1.2.3"]);
+        assert_eq!(client_ip(ip("10.0.0.1"), &forwarded, &proxies()), ip("10.1.2.3"));
+    }
+
+    #[test]
+    fn only_trusted_hops_yield_the_left_most_one() {
+        let forwarded = headers(&["10.9.9.9, 10.1.2.3"]);
+        assert_eq!(client_ip(ip("10.0.0.1"), &forwarded, &proxies()), ip("10.9.9.9"));
+    }
+
+    #[test]
+    fn ipv4_mapped_addresses_are_canonical() {
+        let forwarded = headers(&["::ffff:203.0.113.7"]);
+        assert_eq!(client_ip(ip("::ffff:10.0.0.1"), &forwarded, &proxies()), ip("203.0.113.7"));
+    }
+}
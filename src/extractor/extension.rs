@@ -0,0 +1,8 @@
+use axum::extract::FromRequestParts;
+
+use crate::error::AppError;
+
+/// `axum::Extension` that rejects with an `AppError` when the extension is missing.
+#[derive(FromRequestParts)]
+#[from_request(via(axum::Extension), rejection(AppError))]
+pub struct Extension<T>(pub T);
@@ -0,0 +1,13 @@
+pub mod client_info;
+pub mod extension;
+pub mod locale;
+pub mod path;
+pub mod query;
+pub mod validated_json;
+pub mod validated_query;
+
+pub use extension::Extension;
+pub use path::Path;
+pub use query::Query;
+pub use validated_json::ValidatedJson;
+pub use validated_query::ValidatedQuery;
@@ -0,0 +1,8 @@
+use axum::extract::FromRequestParts;
+
+use crate::error::AppError;
+
+/// `axum::extract::Path` that rejects with an `AppError`, e.g. for a malformed UUID.
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(AppError))]
+pub struct Path<T>(pub T);
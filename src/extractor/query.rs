@@ -0,0 +1,8 @@
+use axum::extract::FromRequestParts;
+
+use crate::error::AppError;
+
+/// `axum::extract::Query` that rejects with an `AppError` when the query string doesn't deserialize.
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(AppError))]
+pub struct Query<T>(pub T);
@@ -0,0 +1,179 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    domain::{
+        api_key_model::{ApiKey, CreatedApiKey},
+        token_model::AuthContext,
+        user_model::User,
+    },
+    error::{AppError, ProblemDetails},
+    extractor::{Extension, Path, ValidatedJson},
+    policy::{authorize, Action, ApiKeyResource},
+    usecase::api_key_usecase::ApiKeyUsecase,
+};
+
+#[derive(Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeySchema {
+    #[validate(length(min = 1, max = 100))]
+    #[schema(example = "CI deploy")]
+    pub name: String,
+    /// Scopes of the key, at most those of the credentials creating it.
+    #[schema(example = json!(["profile:read"]))]
+    pub scopes: Vec<String>,
+    /// Lifetime of the key. Keys without it never expire.
+    #[validate(range(min = 1, max = 3650))]
+    #[schema(example = 90)]
+    pub expires_in_days: Option<i64>,
+}
+
+// --- Own API keys ---
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/api-keys",
+    tag = "API Keys",
+    request_body = CreateApiKeySchema,
+    responses(
+        (status = 201, description = "API key created. The key is only shown in this response", body = CreatedApiKey),
+        (status = 400, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "A requested scope exceeds the current token", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_my_api_key_handler(
+    State(api_key_usecase): State<Arc<dyn ApiKeyUsecase>>,
+    Extension(user): Extension<User>,
+    Extension(context): Extension<AuthContext>,
+    ValidatedJson(body): ValidatedJson<CreateApiKeySchema>,
+) -> Result<impl IntoResponse, AppError> {
+    let api_key = api_key_usecase
+        .create_api_key(user.id, &body.name, body.scopes, body.expires_in_days, &context.scopes)
+        .await?;
+    Ok((StatusCode::CREATED, Json(api_key)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/auth/api-keys",
+    tag = "API Keys",
+    responses(
+        (status = 200, description = "API keys of the current user", body = [ApiKey]),
+        (status = 401, description = "Unauthorized", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_my_api_keys_handler(
+    State(api_key_usecase): State<Arc<dyn ApiKeyUsecase>>,
+    Extension(user): Extension<User>,
+) -> Result<impl IntoResponse, AppError> {
+    let api_keys = api_key_usecase.get_api_keys(user.id).await?;
+    Ok(Json(api_keys))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/auth/api-keys/{id}",
+    tag = "API Keys",
+    params(("id" = Uuid, Path, description = "API key ID")),
+    responses(
+        (status = 204, description = "API key revoked"),
+        (status = 404, description = "API key not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_my_api_key_handler(
+    State(api_key_usecase): State<Arc<dyn ApiKeyUsecase>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    api_key_usecase.revoke_api_key(user.id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// --- API keys of any user ---
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/{id}/api-keys",
+    tag = "API Keys",
+    params(("id" = Uuid, Path, description = "User ID")),
+    request_body = CreateApiKeySchema,
+    responses(
+        (status = 201, description = "API key created. The key is only shown in this response", body = CreatedApiKey),
+        (status = 400, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_user_api_key_handler(
+    State(api_key_usecase): State<Arc<dyn ApiKeyUsecase>>,
+    Extension(actor): Extension<User>,
+    Extension(context): Extension<AuthContext>,
+    Path(user_id): Path<Uuid>,
+    ValidatedJson(body): ValidatedJson<CreateApiKeySchema>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize(&actor, Action::Create, &ApiKeyResource { user_id })?;
+    let api_key = api_key_usecase
+        .create_api_key(user_id, &body.name, body.scopes, body.expires_in_days, &context.scopes)
+        .await?;
+    Ok((StatusCode::CREATED, Json(api_key)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/users/{id}/api-keys",
+    tag = "API Keys",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "API keys of the user", body = [ApiKey]),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_user_api_keys_handler(
+    State(api_key_usecase): State<Arc<dyn ApiKeyUsecase>>,
+    Extension(actor): Extension<User>,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize(&actor, Action::List, &ApiKeyResource { user_id })?;
+    let api_keys = api_key_usecase.get_api_keys(user_id).await?;
+    Ok(Json(api_keys))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/users/{id}/api-keys/{key_id}",
+    tag = "API Keys",
+    params(
+        ("id" = Uuid, Path, description = "User ID"),
+        ("key_id" = Uuid, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 204, description = "API key revoked"),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "API key not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_user_api_key_handler(
+    State(api_key_usecase): State<Arc<dyn ApiKeyUsecase>>,
+    Extension(actor): Extension<User>,
+    Path((user_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize(&actor, Action::Delete, &ApiKeyResource { user_id })?;
+    api_key_usecase.revoke_api_key(user_id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,12 +1,24 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use validator::Validate;
-use utoipa::{path, ToSchema};
+use utoipa::{path, IntoParams, ToSchema};
 
 use crate::{
-    domain::{token_model::TokenResponse, user_model::FilteredUser},
-    error::{AppError, ErrorResponse},
+    domain::{
+        session_model::ClientInfo,
+        token_model::TokenResponse,
+        two_factor_model::{LoginOutcome, MfaChallenge, RecoveryCodes, TwoFactorEnrollment},
+        user_model::{FilteredUser, User},
+    },
+    error::{AppError, ProblemDetails},
+    extractor::{Extension, Query, ValidatedJson},
+    i18n::Locale,
     usecase::auth_usecase::AuthUsecase,
 };
 
@@ -15,29 +27,79 @@ use crate::{
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RegisterSchema {
-    #[validate(length(min = 1, message = "Name is required"))]
+    #[validate(length(min = 1))]
     pub name: String,
-    #[validate(email(message = "Email is invalid"))]
+    #[validate(email)]
     pub email: String,
-    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    /// Checked against the password policy.
     pub password: String,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginSchema {
-    #[validate(email(message = "Email is invalid"))]
+    #[validate(email)]
     pub email: String,
-    #[validate(length(min = 1, message = "Password is required"))]
+    #[validate(length(min = 1))]
     pub password: String,
 }
 
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginTwoFactorSchema {
+    /// Token returned by `/v1/auth/login` when a second factor is required.
+    pub mfa_token: String,
+    /// Code from the authenticator app, or one of the recovery codes.
+    #[validate(length(min = 1, max = 32))]
+    #[schema(example = "123456")]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorCodeSchema {
+    #[validate(length(min = 1, max = 32))]
+    #[schema(example = "123456")]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct RefreshTokenSchema {
     #[serde(rename = "refreshToken")]
+    #[validate(length(min = 1))]
     pub refresh_token: String,
 }
 
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordSchema {
+    #[validate(email)]
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordSchema {
+    /// Checked against the password policy.
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordSchema {
+    #[validate(length(min = 1))]
+    pub current_password: String,
+    /// Checked against the password policy.
+    pub new_password: String,
+    /// Refresh token of the calling session. When given, this session stays signed in.
+    pub refresh_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TokenQuery {
+    pub token: String,
+}
+
 // --- Schema for Response Body ---
 
 #[derive(Serialize, ToSchema)]
@@ -56,15 +118,16 @@ pub struct AuthResponse {
     request_body = RegisterSchema,
     responses(
         (status = 201, description = "User created successfully", body = AuthResponse),
-        (status = 400, description = "Invalid input or email already taken", body = ErrorResponse)
+        (status = 400, description = "Invalid input or email already taken", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "Password hashing is at capacity", body = ProblemDetails, content_type = "application/problem+json", headers(("Retry-After" = u64, description = "Seconds to wait before retrying")))
     )
 )]
 pub async fn register_handler(
     State(auth_usecase): State<Arc<dyn AuthUsecase>>,
-    Json(body): Json<RegisterSchema>,
+    client: ClientInfo,
+    ValidatedJson(body): ValidatedJson<RegisterSchema>,
 ) -> Result<impl IntoResponse, AppError> {
-    body.validate()?;
-    let (user, tokens) = auth_usecase.register(body).await?;
+    let (user, tokens) = auth_usecase.register(body, client).await?;
     let response = AuthResponse { user, tokens };
     Ok((StatusCode::CREATED, Json(response)))
 }
@@ -76,15 +139,44 @@ pub async fn register_handler(
     request_body = LoginSchema,
     responses(
         (status = 200, description = "Login successful", body = AuthResponse),
-        (status = 401, description = "Invalid email or password", body = ErrorResponse)
+        (status = 202, description = "Password accepted, the second factor is required at /v1/auth/login/2fa", body = MfaChallenge),
+        (status = 401, description = "Invalid email or password", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Too many failed logins for the account or IP", body = ProblemDetails, content_type = "application/problem+json", headers(("Retry-After" = u64, description = "Seconds until the lockout ends"))),
+        (status = 503, description = "Password hashing is at capacity", body = ProblemDetails, content_type = "application/problem+json", headers(("Retry-After" = u64, description = "Seconds to wait before retrying")))
     )
 )]
 pub async fn login_handler(
     State(auth_usecase): State<Arc<dyn AuthUsecase>>,
-    Json(body): Json<LoginSchema>,
+    client: ClientInfo,
+    ValidatedJson(body): ValidatedJson<LoginSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    match auth_usecase.login(body, client).await? {
+        LoginOutcome::Authenticated(user, tokens) => {
+            Ok((StatusCode::OK, Json(AuthResponse { user, tokens })).into_response())
+        }
+        LoginOutcome::MfaRequired(challenge) => {
+            Ok((StatusCode::ACCEPTED, Json(challenge)).into_response())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/login/2fa",
+    tag = "Auth",
+    request_body = LoginTwoFactorSchema,
+    responses(
+        (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 401, description = "Invalid code or expired MFA token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Too many failed logins for the account or IP", body = ProblemDetails, content_type = "application/problem+json", headers(("Retry-After" = u64, description = "Seconds until the lockout ends")))
+    )
+)]
+pub async fn login_two_factor_handler(
+    State(auth_usecase): State<Arc<dyn AuthUsecase>>,
+    client: ClientInfo,
+    ValidatedJson(body): ValidatedJson<LoginTwoFactorSchema>,
 ) -> Result<impl IntoResponse, AppError> {
-    body.validate()?;
-    let (user, tokens) = auth_usecase.login(body).await?;
+    let (user, tokens) = auth_usecase.login_two_factor(body, client).await?;
     let response = AuthResponse { user, tokens };
     Ok((StatusCode::OK, Json(response)))
 }
@@ -96,12 +188,12 @@ pub async fn login_handler(
     request_body = RefreshTokenSchema,
     responses(
         (status = 204, description = "Logout successful"),
-        (status = 404, description = "Token not found", body = ErrorResponse)
+        (status = 404, description = "Token not found", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
 pub async fn logout_handler(
     State(auth_usecase): State<Arc<dyn AuthUsecase>>,
-    Json(body): Json<RefreshTokenSchema>,
+    ValidatedJson(body): ValidatedJson<RefreshTokenSchema>,
 ) -> Result<impl IntoResponse, AppError> {
     auth_usecase.logout(body.refresh_token).await?;
     Ok(StatusCode::NO_CONTENT)
@@ -114,13 +206,174 @@ pub async fn logout_handler(
     request_body = RefreshTokenSchema,
     responses(
         (status = 200, description = "Tokens refreshed successfully", body = TokenResponse),
-        (status = 401, description = "Unauthorized or invalid refresh token", body = ErrorResponse)
+        (status = 401, description = "Unauthorized or invalid refresh token", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
 pub async fn refresh_tokens_handler(
     State(auth_usecase): State<Arc<dyn AuthUsecase>>,
-    Json(body): Json<RefreshTokenSchema>,
+    client: ClientInfo,
+    ValidatedJson(body): ValidatedJson<RefreshTokenSchema>,
 ) -> Result<impl IntoResponse, AppError> {
-    let tokens = auth_usecase.refresh_auth(body.refresh_token).await?;
+    let tokens = auth_usecase.refresh_auth(body.refresh_token, client).await?;
     Ok((StatusCode::OK, Json(tokens)))
-}
\ No newline at end of file
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/send-verification-email",
+    tag = "Auth",
+    responses(
+        (status = 204, description = "Verification email sent"),
+        (status = 400, description = "Email is already verified", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Unauthorized", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn send_verification_email_handler(
+    State(auth_usecase): State<Arc<dyn AuthUsecase>>,
+    Extension(user): Extension<User>,
+    locale: Locale,
+) -> Result<impl IntoResponse, AppError> {
+    auth_usecase.send_verification_email(user, locale).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/verify-email",
+    tag = "Auth",
+    params(TokenQuery),
+    responses(
+        (status = 204, description = "Email verified successfully"),
+        (status = 401, description = "Email verification failed", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn verify_email_handler(
+    State(auth_usecase): State<Arc<dyn AuthUsecase>>,
+    Query(query): Query<TokenQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    auth_usecase.verify_email(query.token).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/forgot-password",
+    tag = "Auth",
+    request_body = ForgotPasswordSchema,
+    responses(
+        (status = 204, description = "A reset link is sent if the email is registered"),
+        (status = 400, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn forgot_password_handler(
+    State(auth_usecase): State<Arc<dyn AuthUsecase>>,
+    locale: Locale,
+    ValidatedJson(body): ValidatedJson<ForgotPasswordSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    auth_usecase.forgot_password(body.email, locale).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/reset-password",
+    tag = "Auth",
+    params(TokenQuery),
+    request_body = ResetPasswordSchema,
+    responses(
+        (status = 204, description = "Password reset successfully"),
+        (status = 400, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Password reset failed", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "Password hashing is at capacity", body = ProblemDetails, content_type = "application/problem+json", headers(("Retry-After" = u64, description = "Seconds to wait before retrying")))
+    )
+)]
+pub async fn reset_password_handler(
+    State(auth_usecase): State<Arc<dyn AuthUsecase>>,
+    Query(query): Query<TokenQuery>,
+    ValidatedJson(body): ValidatedJson<ResetPasswordSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    auth_usecase.reset_password(query.token, body.password).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/change-password",
+    tag = "Auth",
+    request_body = ChangePasswordSchema,
+    responses(
+        (status = 204, description = "Password changed and other sessions revoked"),
+        (status = 400, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Current password is incorrect", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "Password hashing is at capacity", body = ProblemDetails, content_type = "application/problem+json", headers(("Retry-After" = u64, description = "Seconds to wait before retrying")))
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn change_password_handler(
+    State(auth_usecase): State<Arc<dyn AuthUsecase>>,
+    Extension(user): Extension<User>,
+    ValidatedJson(body): ValidatedJson<ChangePasswordSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    auth_usecase.change_password(user, body).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/2fa/enroll",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Enrollment started, confirm it with a code from the authenticator app", body = TwoFactorEnrollment),
+        (status = 400, description = "Two-factor authentication is already enabled", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn enroll_two_factor_handler(
+    State(auth_usecase): State<Arc<dyn AuthUsecase>>,
+    Extension(user): Extension<User>,
+) -> Result<impl IntoResponse, AppError> {
+    let enrollment = auth_usecase.enroll_two_factor(user).await?;
+    Ok(Json(enrollment))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/2fa/confirm",
+    tag = "Auth",
+    request_body = TwoFactorCodeSchema,
+    responses(
+        (status = 200, description = "Two-factor authentication enabled", body = RecoveryCodes),
+        (status = 400, description = "Invalid code or no enrollment started", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn confirm_two_factor_handler(
+    State(auth_usecase): State<Arc<dyn AuthUsecase>>,
+    Extension(user): Extension<User>,
+    ValidatedJson(body): ValidatedJson<TwoFactorCodeSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    let recovery_codes = auth_usecase.confirm_two_factor(user, body.code).await?;
+    Ok(Json(recovery_codes))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/2fa/disable",
+    tag = "Auth",
+    request_body = TwoFactorCodeSchema,
+    responses(
+        (status = 204, description = "Two-factor authentication disabled"),
+        (status = 400, description = "Two-factor authentication is not enabled", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Invalid code", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn disable_two_factor_handler(
+    State(auth_usecase): State<Arc<dyn AuthUsecase>>,
+    Extension(user): Extension<User>,
+    ValidatedJson(body): ValidatedJson<TwoFactorCodeSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    auth_usecase.disable_two_factor(user, body.code).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
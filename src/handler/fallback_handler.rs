@@ -0,0 +1,11 @@
+use crate::error::{AppError, ErrorCode};
+
+/// Answers requests that match no route.
+pub async fn not_found_handler() -> AppError {
+    AppError::NotFound(ErrorCode::RouteNotFound.into())
+}
+
+/// Answers requests whose path matches a route but whose method doesn't.
+pub async fn method_not_allowed_handler() -> AppError {
+    AppError::MethodNotAllowed
+}
@@ -0,0 +1,16 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use std::sync::Arc;
+
+use crate::jwt::JwtKeys;
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Public keys that verify issued JWTs, empty when tokens are signed with HS256", body = Object)
+    )
+)]
+pub async fn get_jwks_handler(State(jwt_keys): State<Arc<JwtKeys>>) -> impl IntoResponse {
+    Json(jwt_keys.jwks().clone())
+}
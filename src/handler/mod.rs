@@ -1,2 +1,7 @@
 pub mod auth_handler;
-pub mod user_handler;
\ No newline at end of file
+pub mod user_handler;
+pub mod role_handler;
+pub mod session_handler;
+pub mod jwks_handler;
+pub mod api_key_handler;
+pub mod fallback_handler;
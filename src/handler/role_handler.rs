@@ -0,0 +1,158 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    domain::role_model::{Permission, Role},
+    error::{AppError, ProblemDetails},
+    extractor::{Path, ValidatedJson},
+    usecase::role_usecase::RoleUsecase,
+};
+
+#[derive(Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRoleSchema {
+    #[validate(length(min = 1, max = 64))]
+    #[schema(example = "support")]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    #[schema(example = json!(["users:read"]))]
+    pub permissions: Vec<String>,
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoleSchema {
+    pub description: Option<String>,
+    /// Replaces every permission of the role when given.
+    pub permissions: Option<Vec<String>>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/roles",
+    tag = "Roles",
+    request_body = CreateRoleSchema,
+    responses(
+        (status = 201, description = "Role created", body = Role),
+        (status = 400, description = "Invalid input or role already exists", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_role_handler(
+    State(role_usecase): State<Arc<dyn RoleUsecase>>,
+    ValidatedJson(body): ValidatedJson<CreateRoleSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    let role = role_usecase
+        .create_role(&body.name, &body.description, body.permissions)
+        .await?;
+    Ok((StatusCode::CREATED, Json(role)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/roles",
+    tag = "Roles",
+    responses(
+        (status = 200, description = "List of roles", body = [Role]),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_roles_handler(
+    State(role_usecase): State<Arc<dyn RoleUsecase>>,
+) -> Result<impl IntoResponse, AppError> {
+    let roles = role_usecase.get_roles().await?;
+    Ok(Json(roles))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/roles/{id}",
+    tag = "Roles",
+    params(("id" = Uuid, Path, description = "Role ID")),
+    responses(
+        (status = 200, description = "Role found", body = Role),
+        (status = 404, description = "Role not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_role_handler(
+    State(role_usecase): State<Arc<dyn RoleUsecase>>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let role = role_usecase.get_role_by_id(id).await?;
+    Ok(Json(role))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/v1/roles/{id}",
+    tag = "Roles",
+    params(("id" = Uuid, Path, description = "Role ID")),
+    request_body = UpdateRoleSchema,
+    responses(
+        (status = 200, description = "Role updated", body = Role),
+        (status = 400, description = "Invalid input", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Role not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_role_handler(
+    State(role_usecase): State<Arc<dyn RoleUsecase>>,
+    Path(id): Path<Uuid>,
+    ValidatedJson(body): ValidatedJson<UpdateRoleSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    let role = role_usecase
+        .update_role(id, body.description, body.permissions)
+        .await?;
+    Ok(Json(role))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/roles/{id}",
+    tag = "Roles",
+    params(("id" = Uuid, Path, description = "Role ID")),
+    responses(
+        (status = 204, description = "Role deleted"),
+        (status = 400, description = "System role or role still in use", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Role not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_role_handler(
+    State(role_usecase): State<Arc<dyn RoleUsecase>>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    role_usecase.delete_role(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/permissions",
+    tag = "Roles",
+    responses(
+        (status = 200, description = "List of permissions", body = [Permission]),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_permissions_handler(
+    State(role_usecase): State<Arc<dyn RoleUsecase>>,
+) -> Result<impl IntoResponse, AppError> {
+    let permissions = role_usecase.get_permissions().await?;
+    Ok(Json(permissions))
+}
@@ -0,0 +1,145 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    domain::{session_model::Session, user_model::User},
+    error::{AppError, ProblemDetails},
+    extractor::{Extension, Path},
+    policy::{authorize, Action, SessionResource},
+    usecase::session_usecase::SessionUsecase,
+};
+
+// --- Own sessions ---
+
+#[utoipa::path(
+    get,
+    path = "/v1/auth/sessions",
+    tag = "Sessions",
+    responses(
+        (status = 200, description = "Active sessions of the current user", body = [Session]),
+        (status = 401, description = "Unauthorized", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_my_sessions_handler(
+    State(session_usecase): State<Arc<dyn SessionUsecase>>,
+    Extension(user): Extension<User>,
+) -> Result<impl IntoResponse, AppError> {
+    let sessions = session_usecase.get_sessions(user.id).await?;
+    Ok(Json(sessions))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/auth/sessions/{id}",
+    tag = "Sessions",
+    params(("id" = Uuid, Path, description = "Session ID")),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 404, description = "Session not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_my_session_handler(
+    State(session_usecase): State<Arc<dyn SessionUsecase>>,
+    Extension(user): Extension<User>,
+    Path(session_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    session_usecase.revoke_session(user.id, session_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/auth/sessions",
+    tag = "Sessions",
+    responses(
+        (status = 204, description = "All sessions revoked"),
+        (status = 401, description = "Unauthorized", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_my_sessions_handler(
+    State(session_usecase): State<Arc<dyn SessionUsecase>>,
+    Extension(user): Extension<User>,
+) -> Result<impl IntoResponse, AppError> {
+    session_usecase.revoke_all_sessions(user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// --- Sessions of any user ---
+
+#[utoipa::path(
+    get,
+    path = "/v1/users/{id}/sessions",
+    tag = "Sessions",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Active sessions of the user", body = [Session]),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_user_sessions_handler(
+    State(session_usecase): State<Arc<dyn SessionUsecase>>,
+    Extension(actor): Extension<User>,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize(&actor, Action::List, &SessionResource { user_id })?;
+    let sessions = session_usecase.get_sessions(user_id).await?;
+    Ok(Json(sessions))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/users/{id}/sessions/{session_id}",
+    tag = "Sessions",
+    params(
+        ("id" = Uuid, Path, description = "User ID"),
+        ("session_id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Session not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_user_session_handler(
+    State(session_usecase): State<Arc<dyn SessionUsecase>>,
+    Extension(actor): Extension<User>,
+    Path((user_id, session_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize(&actor, Action::Delete, &SessionResource { user_id })?;
+    session_usecase.revoke_session(user_id, session_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/users/{id}/sessions",
+    tag = "Sessions",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 204, description = "All sessions of the user revoked"),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_user_sessions_handler(
+    State(session_usecase): State<Arc<dyn SessionUsecase>>,
+    Extension(actor): Extension<User>,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize(&actor, Action::Delete, &SessionResource { user_id })?;
+    session_usecase.revoke_all_sessions(user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
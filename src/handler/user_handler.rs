@@ -1,9 +1,10 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::State,
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use std::sync::Arc;
 use utoipa::{IntoParams, ToSchema};
@@ -11,33 +12,84 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    domain::user_model::{FilteredUser, Role},
-    error::{AppError, ErrorResponse},
-    repository::user_repository::{PaginatedResult, UserQueryOptions},
+    domain::user_model::{FilteredUser, User},
+    error::{AppError, ProblemDetails},
+    extractor::{Extension, Path, ValidatedJson, ValidatedQuery},
+    i18n::validate_locale,
+    policy::{authorize, Action, UserResource},
+    repository::user_repository::{PaginatedResult, UserQueryOptions, UserSort},
     usecase::user_usecase::UserUsecase,
 };
 
-#[derive(Deserialize, ToSchema)]
+#[derive(Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateUserSchema {
+    #[validate(length(min = 1))]
     pub name: String,
+    #[validate(email)]
     pub email: String,
+    /// Checked against the password policy.
+    #[validate(length(min = 1))]
     pub password: String,
-    pub role: Role,
+    #[validate(length(min = 1))]
+    #[schema(example = "user")]
+    pub role: String,
 }
 
-#[derive(Deserialize, ToSchema)]
+#[derive(Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserSchema {
+    #[validate(length(min = 1))]
     pub name: Option<String>,
+    #[validate(email)]
     pub email: Option<String>,
+    /// Checked against the password policy. Changing it signs the user out of every session.
+    #[validate(length(min = 1))]
     pub password: Option<String>,
+    #[validate(length(min = 1))]
+    pub role: Option<String>,
 }
 
-#[derive(Debug, Deserialize, IntoParams)]
+#[derive(Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProfileSchema {
+    #[validate(length(min = 1))]
+    pub name: Option<String>,
+    #[validate(email)]
+    pub email: Option<String>,
+    /// Checked against the password policy. Changing it signs out every session, this one included.
+    pub password: Option<String>,
+    /// Required when changing the email or the password.
+    pub current_password: Option<String>,
+    /// Preferred language for messages and emails, overriding `Accept-Language`.
+    #[validate(custom(function = "validate_locale"))]
+    #[schema(example = "id")]
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 pub struct GetUsersQuery {
+    /// 1-based, defaults to 1.
+    #[validate(range(min = 1))]
     pub page: Option<u32>,
+    /// Users per page, defaults to 10 and is capped at 100.
     pub limit: Option<u32>,
+    /// Only users with this role.
+    #[param(example = "admin")]
+    pub role: Option<String>,
+    pub is_email_verified: Option<bool>,
+    /// Only users created at or after this time (RFC 3339).
+    pub created_from: Option<DateTime<Utc>>,
+    /// Only users created at or before this time (RFC 3339).
+    pub created_to: Option<DateTime<Utc>>,
+    /// Case-insensitive match on part of the name or email.
+    pub search: Option<String>,
+    /// `field:asc|desc` with field one of `name`, `email`, `role`, `createdAt`, `updatedAt`.
+    /// Defaults to `createdAt:desc`.
+    #[param(value_type = Option<String>, example = "name:asc")]
+    pub sort_by: Option<UserSort>,
 }
 
 #[utoipa::path(
@@ -47,16 +99,18 @@ pub struct GetUsersQuery {
     request_body = CreateUserSchema,
     responses(
         (status = 201, description = "User created", body = FilteredUser),
-        (status = 403, description = "Forbidden", body = ErrorResponse)
+        (status = 403, description = "Missing permission, or the role grants permissions the caller doesn't hold", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "Password hashing is at capacity", body = ProblemDetails, content_type = "application/problem+json", headers(("Retry-After" = u64, description = "Seconds to wait before retrying")))
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn create_user_handler(
     State(user_usecase): State<Arc<dyn UserUsecase>>,
-    Json(body): Json<CreateUserSchema>,
+    Extension(actor): Extension<User>,
+    ValidatedJson(body): ValidatedJson<CreateUserSchema>,
 ) -> Result<impl IntoResponse, AppError> {
     let new_user = user_usecase
-        .create_user(&body.name, &body.email, &body.password, body.role)
+        .create_user(&actor, &body.name, &body.email, &body.password, &body.role)
         .await?;
     Ok((StatusCode::CREATED, Json(new_user)))
 }
@@ -68,17 +122,24 @@ pub async fn create_user_handler(
     params(GetUsersQuery),
     responses(
         (status = 200, description = "List of users", body = PaginatedUsers),
-        (status = 403, description = "Forbidden", body = ErrorResponse)
+        (status = 400, description = "Invalid page, filter or sort", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json")
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn get_users_handler(
     State(user_usecase): State<Arc<dyn UserUsecase>>,
-    Query(params): Query<GetUsersQuery>,
+    ValidatedQuery(params): ValidatedQuery<GetUsersQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let options = UserQueryOptions {
         page: params.page,
         limit: params.limit,
+        role: params.role,
+        is_email_verified: params.is_email_verified,
+        created_from: params.created_from,
+        created_to: params.created_to,
+        search: params.search,
+        sort_by: params.sort_by,
     };
     let users = user_usecase.get_users(options).await?;
     Ok(Json(users))
@@ -91,14 +152,17 @@ pub async fn get_users_handler(
     params(("id" = Uuid, Path, description = "User ID")),
     responses(
         (status = 200, description = "User found", body = FilteredUser),
-        (status = 404, description = "User not found", body = ErrorResponse)
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json")
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn get_user_handler(
     State(user_usecase): State<Arc<dyn UserUsecase>>,
+    Extension(actor): Extension<User>,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize(&actor, Action::Read, &UserResource { id })?;
     let user = user_usecase.get_user_by_id(id).await?;
     Ok(Json(user))
 }
@@ -111,17 +175,21 @@ pub async fn get_user_handler(
     request_body = UpdateUserSchema,
     responses(
         (status = 200, description = "User updated", body = FilteredUser),
-        (status = 404, description = "User not found", body = ErrorResponse)
+        (status = 403, description = "Missing permission, own role change, or changing the login or role of a more privileged user", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "Password hashing is at capacity", body = ProblemDetails, content_type = "application/problem+json", headers(("Retry-After" = u64, description = "Seconds to wait before retrying")))
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn update_user_handler(
     State(user_usecase): State<Arc<dyn UserUsecase>>,
+    Extension(actor): Extension<User>,
     Path(id): Path<Uuid>,
-    Json(body): Json<UpdateUserSchema>,
+    ValidatedJson(body): ValidatedJson<UpdateUserSchema>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize(&actor, Action::Update, &UserResource { id })?;
     let updated_user = user_usecase
-        .update_user(id, body.name, body.email, body.password)
+        .update_user(&actor, id, body.name, body.email, body.password, body.role)
         .await?;
     Ok(Json(updated_user))
 }
@@ -133,14 +201,95 @@ pub async fn update_user_handler(
     params(("id" = Uuid, Path, description = "User ID")),
     responses(
         (status = 204, description = "User deleted"),
-        (status = 404, description = "User not found", body = ErrorResponse)
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json")
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn delete_user_handler(
     State(user_usecase): State<Arc<dyn UserUsecase>>,
+    Extension(actor): Extension<User>,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize(&actor, Action::Delete, &UserResource { id })?;
     user_usecase.delete_user(id).await?;
     Ok(StatusCode::NO_CONTENT)
-}
\ No newline at end of file
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/{id}/unlock",
+    tag = "Users",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 204, description = "Failed logins of the user forgotten and any lockout lifted"),
+        (status = 403, description = "Forbidden", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn unlock_user_handler(
+    State(user_usecase): State<Arc<dyn UserUsecase>>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    user_usecase.unlock_user(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/users/me",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Current user", body = FilteredUser),
+        (status = 401, description = "Unauthorized", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_me_handler(
+    Extension(user): Extension<User>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(FilteredUser::from(user)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/v1/users/me",
+    tag = "Users",
+    request_body = UpdateProfileSchema,
+    responses(
+        (status = 200, description = "Profile updated", body = FilteredUser),
+        (status = 400, description = "Invalid input, email already taken or current password missing", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Current password is incorrect", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "Password hashing is at capacity", body = ProblemDetails, content_type = "application/problem+json", headers(("Retry-After" = u64, description = "Seconds to wait before retrying")))
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_me_handler(
+    State(user_usecase): State<Arc<dyn UserUsecase>>,
+    Extension(user): Extension<User>,
+    ValidatedJson(body): ValidatedJson<UpdateProfileSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    let updated_user = user_usecase
+        .update_profile(user, body.name, body.email, body.password, body.current_password, body.locale)
+        .await?;
+    Ok(Json(updated_user))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/users/me",
+    tag = "Users",
+    responses(
+        (status = 204, description = "Account deleted"),
+        (status = 401, description = "Unauthorized", body = ProblemDetails, content_type = "application/problem+json")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_me_handler(
+    State(user_usecase): State<Arc<dyn UserUsecase>>,
+    Extension(user): Extension<User>,
+) -> Result<impl IntoResponse, AppError> {
+    user_usecase.delete_user(user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
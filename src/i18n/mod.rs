@@ -0,0 +1,197 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use validator::ValidationError;
+
+use crate::config::CONFIG;
+
+mod template;
+
+const EN: &str = include_str!("locales/en.toml");
+const ID: &str = include_str!("locales/id.toml");
+
+/// Languages the message catalog is available in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    En,
+    Id,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Id];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Id => "id",
+        }
+    }
+
+    /// Matches a language tag such as `id`, `id-ID` or `en-GB` on its primary subtag.
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let primary = tag.split(['-', '_']).next()?.trim().to_ascii_lowercase();
+        Locale::ALL.into_iter().find(|locale| locale.as_str() == primary)
+    }
+
+    /// Picks the supported language with the highest quality from an `Accept-Language` header.
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut candidates: Vec<(Locale, f32)> = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let locale = Locale::parse(parts.next()?)?;
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (quality > 0.0).then_some((locale, quality))
+            })
+            .collect();
+        // Stable, so equally weighted languages keep the client's order
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.first().map(|(locale, _)| *locale)
+    }
+
+    /// `DEFAULT_LOCALE`, used when neither the user nor the request names a supported language.
+    pub fn default_locale() -> Locale {
+        *DEFAULT_LOCALE
+    }
+}
+
+/// Validator for fields holding a language tag.
+pub fn validate_locale(tag: &str) -> Result<(), ValidationError> {
+    match Locale::parse(tag) {
+        Some(_) => Ok(()),
+        None => Err(ValidationError::new("unsupported_locale")),
+    }
+}
+
+static DEFAULT_LOCALE: Lazy<Locale> = Lazy::new(|| {
+    Locale::parse(&CONFIG.default_locale).unwrap_or_else(|| {
+        tracing::warn!("Unsupported DEFAULT_LOCALE {:?}, falling back to en", CONFIG.default_locale);
+        Locale::En
+    })
+});
+
+/// Every catalog entry is a list of alternatives, tried in order: the first one
+/// whose placeholders all have a value is used, so the most specific wording
+/// comes first. Translations keep the same alternatives in the same order.
+type Catalog = HashMap<String, Vec<String>>;
+
+static CATALOGS: Lazy<HashMap<Locale, Catalog>> = Lazy::new(|| {
+    [(Locale::En, EN), (Locale::Id, ID)]
+        .into_iter()
+        .map(|(locale, source)| {
+            let table: toml::Table = toml::from_str(source)
+                .unwrap_or_else(|err| panic!("Invalid {} message catalog: {}", locale.as_str(), err));
+            let mut catalog = Catalog::new();
+            flatten("", &table, &mut catalog);
+            (locale, catalog)
+        })
+        .collect()
+});
+
+fn flatten(prefix: &str, table: &toml::Table, catalog: &mut Catalog) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            toml::Value::Table(table) => flatten(&key, table, catalog),
+            toml::Value::String(text) => {
+                catalog.insert(key, vec![text.clone()]);
+            }
+            toml::Value::Array(items) => {
+                let texts = items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect();
+                catalog.insert(key, texts);
+            }
+            _ => tracing::warn!("Ignoring non-text catalog entry {}", key),
+        }
+    }
+}
+
+fn entries(locale: Locale, key: &str) -> Option<&'static [String]> {
+    CATALOGS.get(&locale)?.get(key).map(Vec::as_slice)
+}
+
+/// Renders the entry under `key` in `locale`, falling back to English when
+/// the locale's catalog lacks it. Returns `None` when no alternative can be
+/// filled in from `args`.
+pub fn lookup(locale: Locale, key: &str, args: &[(&str, &str)]) -> Option<String> {
+    let args: HashMap<&str, &str> = args.iter().copied().collect();
+    [locale, Locale::En]
+        .into_iter()
+        .find_map(|locale| entries(locale, key))?
+        .iter()
+        .find_map(|template| template::render(template, &args))
+}
+
+/// Like [`lookup`], for entries that always exist, e.g. an email subject.
+/// Falls back to the key itself.
+pub fn text(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    lookup(locale, key, args).unwrap_or_else(|| key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_primary_subtag() {
+        assert_eq!(Locale::parse("id"), Some(Locale::Id));
+        assert_eq!(Locale::parse("id-ID"), Some(Locale::Id));
+        assert_eq!(Locale::parse(" EN_gb "), Some(Locale::En));
+        assert_eq!(Locale::parse("fr-FR"), None);
+        assert_eq!(Locale::parse(""), None);
+    }
+
+    #[test]
+    fn picks_the_highest_quality() {
+        assert_eq!(Locale::from_accept_language("en;q=0.5, id;q=0.8"), Some(Locale::Id));
+        assert_eq!(Locale::from_accept_language("id-ID;q=0.9, en"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language("fr, id;q=0.1"), Some(Locale::Id));
+    }
+
+    #[test]
+    fn equal_qualities_keep_the_client_order() {
+        assert_eq!(Locale::from_accept_language("id, en"), Some(Locale::Id));
+        assert_eq!(Locale::from_accept_language("en;q=0.7, id;q=0.7"), Some(Locale::En));
+    }
+
+    #[test]
+    fn q_zero_excludes_a_language() {
+        assert_eq!(Locale::from_accept_language("id;q=0, en;q=0.1"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language("en;q=0.0"), None);
+    }
+
+    #[test]
+    fn skips_unsupported_and_malformed_entries() {
+        assert_eq!(Locale::from_accept_language("*, fr;q=0.9"), None);
+        assert_eq!(Locale::from_accept_language("id;q=high, en;q=0.2"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language(""), None);
+    }
+
+    #[test]
+    fn lookup_uses_the_first_alternative_that_can_be_filled() {
+        let key = "validation.fields.code.length";
+        assert_eq!(
+            lookup(Locale::En, key, &[("min", "6"), ("max", "32")]).as_deref(),
+            Some("Code must be between 6 and 32 characters")
+        );
+        assert_eq!(lookup(Locale::En, key, &[("min", "6")]).as_deref(), Some("Code is required"));
+    }
+
+    #[test]
+    fn lookup_of_an_unknown_key_fails() {
+        assert_eq!(lookup(Locale::Id, "errors.NO_SUCH_CODE", &[]), None);
+        assert_eq!(text(Locale::Id, "errors.NO_SUCH_CODE", &[]), "errors.NO_SUCH_CODE");
+    }
+
+    #[test]
+    fn translations_have_the_same_entries() {
+        let mut en: Vec<_> = CATALOGS[&Locale::En].iter().map(|(key, texts)| (key, texts.len())).collect();
+        let mut id: Vec<_> = CATALOGS[&Locale::Id].iter().map(|(key, texts)| (key, texts.len())).collect();
+        en.sort();
+        id.sort();
+        assert_eq!(en, id);
+    }
+}
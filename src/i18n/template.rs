@@ -0,0 +1,52 @@
+//! Catalog templates: plain text with `{name}` placeholders.
+
+use std::collections::HashMap;
+
+/// Fills in every placeholder, or returns `None` if one has no value. Values
+/// are inserted as they are, never parsed again.
+pub fn render(template: &str, args: &HashMap<&str, &str>) -> Option<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else { break };
+        rendered.push_str(&rest[..start]);
+        rendered.push_str(args.get(&rest[start + 1..start + len])?);
+        rest = &rest[start + len + 1..];
+    }
+    rendered.push_str(rest);
+    Some(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args<'a>(pairs: &[(&'a str, &'a str)]) -> HashMap<&'a str, &'a str> {
+        pairs.iter().copied().collect()
+    }
+
+    #[test]
+    fn fills_every_placeholder() {
+        let rendered = render("Must be between {min} and {max}, not {min}", &args(&[("min", "1"), ("max", "9")]));
+        assert_eq!(rendered.as_deref(), Some("Must be between 1 and 9, not 1"));
+    }
+
+    #[test]
+    fn missing_value_fails_the_whole_template() {
+        assert_eq!(render("Between {min} and {max}", &args(&[("min", "1")])), None);
+        assert_eq!(render("{missing}", &args(&[])), None);
+    }
+
+    #[test]
+    fn values_are_not_rendered_again() {
+        let rendered = render("Hello {name}", &args(&[("name", "{name}"), ("other", "x")]));
+        assert_eq!(rendered.as_deref(), Some("Hello {name}"));
+    }
+
+    #[test]
+    fn text_without_a_closing_brace_is_kept() {
+        assert_eq!(render("No placeholders", &args(&[])).as_deref(), Some("No placeholders"));
+        assert_eq!(render("Unclosed {brace", &args(&[])).as_deref(), Some("Unclosed {brace"));
+        assert_eq!(render("{a} then {", &args(&[("a", "1")])).as_deref(), Some("1 then {"));
+    }
+}
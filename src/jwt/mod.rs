@@ -0,0 +1,256 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    decode, decode_header, encode,
+    errors::{Error as JwtError, ErrorKind},
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters,
+        EllipticCurveKeyType, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType,
+        PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use simple_asn1::ASN1Block;
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
+
+use crate::{config::CONFIG, error::AppError};
+
+enum VerificationKeys {
+    /// HS256: the shared `JWT_SECRET`.
+    Secret(DecodingKey),
+    /// Asymmetric algorithms: public keys by `kid`.
+    Public(HashMap<String, DecodingKey>),
+}
+
+/// Keys used to sign and verify every JWT issued by the service.
+///
+/// With `JWT_ALGORITHM=HS256` (the default) tokens are signed with `JWT_SECRET`.
+/// With RS256, ES256 or EdDSA they are signed with `JWT_PRIVATE_KEY_PATH` and carry
+/// `JWT_KEY_ID` as `kid`. Every `<kid>.pem` public key in `JWT_PUBLIC_KEYS_DIR` is
+/// accepted for verification and published as JWKS, so a key can be rotated by adding
+/// the new public key, switching the signing key, and removing the old public key once
+/// its tokens have expired.
+pub struct JwtKeys {
+    algorithm: Algorithm,
+    key_id: Option<String>,
+    signing_key: EncodingKey,
+    verification_keys: VerificationKeys,
+    validation: Validation,
+    jwks: JwkSet,
+}
+
+/// Loads the keys selected by `JWT_ALGORITHM`.
+pub fn create_jwt_keys() -> Result<Arc<JwtKeys>, AppError> {
+    let keys = match CONFIG.jwt_algorithm {
+        Algorithm::HS256 => JwtKeys::from_secret(&CONFIG.jwt_secret),
+        Algorithm::RS256 | Algorithm::ES256 | Algorithm::EdDSA => JwtKeys::from_pem_files(
+            CONFIG.jwt_algorithm,
+            required_setting(&CONFIG.jwt_key_id, "JWT_KEY_ID")?,
+            required_setting(&CONFIG.jwt_private_key_path, "JWT_PRIVATE_KEY_PATH")?,
+            required_setting(&CONFIG.jwt_public_keys_dir, "JWT_PUBLIC_KEYS_DIR")?,
+        )?,
+        algorithm => return Err(key_error(format!("Unsupported JWT_ALGORITHM {:?}", algorithm))),
+    };
+    Ok(Arc::new(keys.with_issuer_and_audience(&CONFIG.jwt_issuer, &CONFIG.jwt_audience)))
+}
+
+impl JwtKeys {
+    pub fn from_secret(secret: &str) -> Self {
+        Self {
+            algorithm: Algorithm::HS256,
+            key_id: None,
+            signing_key: EncodingKey::from_secret(secret.as_ref()),
+            verification_keys: VerificationKeys::Secret(DecodingKey::from_secret(secret.as_ref())),
+            validation: Validation::new(Algorithm::HS256),
+            // A shared secret must never be published
+            jwks: JwkSet { keys: Vec::new() },
+        }
+    }
+
+    pub fn from_pem_files(
+        algorithm: Algorithm,
+        key_id: &str,
+        private_key_path: &str,
+        public_keys_dir: &str,
+    ) -> Result<Self, AppError> {
+        let private_pem = read_file(Path::new(private_key_path))?;
+        let signing_key = match algorithm {
+            Algorithm::RS256 => EncodingKey::from_rsa_pem(&private_pem),
+            Algorithm::ES256 => EncodingKey::from_ec_pem(&private_pem),
+            _ => EncodingKey::from_ed_pem(&private_pem),
+        }
+        .map_err(|err| key_error(format!("Invalid private key {}: {}", private_key_path, err)))?;
+
+        let mut decoding_keys = HashMap::new();
+        let mut jwks = Vec::new();
+        let entries = fs::read_dir(public_keys_dir)
+            .map_err(|err| key_error(format!("Cannot read {}: {}", public_keys_dir, err)))?;
+        for entry in entries {
+            let path = entry.map_err(|err| key_error(err.to_string()))?.path();
+            let Some(kid) = path.file_name().and_then(|name| name.to_str()).and_then(|name| name.strip_suffix(".pem"))
+            else {
+                continue;
+            };
+
+            let public_pem = read_file(&path)?;
+            let decoding_key = match algorithm {
+                Algorithm::RS256 => DecodingKey::from_rsa_pem(&public_pem),
+                Algorithm::ES256 => DecodingKey::from_ec_pem(&public_pem),
+                _ => DecodingKey::from_ed_pem(&public_pem),
+            }
+            .map_err(|err| key_error(format!("Invalid public key {}: {}", path.display(), err)))?;
+
+            jwks.push(public_jwk(algorithm, kid, &public_pem)?);
+            decoding_keys.insert(kid.to_string(), decoding_key);
+        }
+
+        if !decoding_keys.contains_key(key_id) {
+            return Err(key_error(format!(
+                "The public key of the signing key is missing, expected {}/{}.pem",
+                public_keys_dir, key_id
+            )));
+        }
+        // Stable order, so the JWKS doesn't change between restarts
+        jwks.sort_by(|a, b| a.common.key_id.cmp(&b.common.key_id));
+
+        Ok(Self {
+            algorithm,
+            key_id: Some(key_id.to_string()),
+            signing_key,
+            verification_keys: VerificationKeys::Public(decoding_keys),
+            validation: Validation::new(algorithm),
+            jwks: JwkSet { keys: jwks },
+        })
+    }
+
+    /// Only accepts tokens whose `iss` and `aud` claims match.
+    pub fn with_issuer_and_audience(mut self, issuer: &str, audience: &str) -> Self {
+        self.validation.set_issuer(&[issuer]);
+        self.validation.set_audience(&[audience]);
+        self.validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+        self
+    }
+
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String, AppError> {
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.key_id.clone();
+        Ok(encode(&header, claims, &self.signing_key)?)
+    }
+
+    /// Verifies the signature and expiry of `token` and returns its claims.
+    pub fn decode<T: DeserializeOwned>(&self, token: &str) -> Result<T, AppError> {
+        let decoding_key = match &self.verification_keys {
+            VerificationKeys::Secret(key) => key,
+            VerificationKeys::Public(keys) => {
+                let kid = decode_header(token)?.kid.ok_or_else(invalid_token)?;
+                keys.get(&kid).ok_or_else(invalid_token)?
+            }
+        };
+        Ok(decode::<T>(token, decoding_key, &self.validation)?.claims)
+    }
+
+    /// Public verification keys, empty for HS256.
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+fn invalid_token() -> JwtError {
+    ErrorKind::InvalidToken.into()
+}
+
+fn required_setting<'a>(value: &'a Option<String>, name: &str) -> Result<&'a str, AppError> {
+    value.as_deref().ok_or_else(|| {
+        key_error(format!("{} is required when JWT_ALGORITHM is {:?}", name, CONFIG.jwt_algorithm))
+    })
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, AppError> {
+    fs::read(path).map_err(|err| key_error(format!("Cannot read {}: {}", path.display(), err)))
+}
+
+fn key_error(message: String) -> AppError {
+    tracing::error!("JWT key configuration: {}", message);
+    AppError::InternalServerError
+}
+
+/// Builds the JWK of a PEM encoded public key.
+fn public_jwk(algorithm: Algorithm, kid: &str, public_pem: &[u8]) -> Result<Jwk, AppError> {
+    let invalid = || key_error(format!("Unsupported public key format for kid {}", kid));
+
+    let pem = pem::parse(public_pem).map_err(|_| invalid())?;
+    let (key_algorithm, parameters) = match (algorithm, pem.tag()) {
+        (Algorithm::RS256, "RSA PUBLIC KEY") => {
+            (KeyAlgorithm::RS256, rsa_parameters(pem.contents()).ok_or_else(invalid)?)
+        }
+        (Algorithm::RS256, "PUBLIC KEY") => {
+            let key = subject_public_key(pem.contents()).ok_or_else(invalid)?;
+            (KeyAlgorithm::RS256, rsa_parameters(&key).ok_or_else(invalid)?)
+        }
+        (Algorithm::ES256, "PUBLIC KEY") => {
+            // Uncompressed point: 0x04 || x || y
+            let key = subject_public_key(pem.contents()).ok_or_else(invalid)?;
+            if key.len() != 65 || key[0] != 0x04 {
+                return Err(invalid());
+            }
+            let parameters = AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                key_type: EllipticCurveKeyType::EC,
+                curve: EllipticCurve::P256,
+                x: URL_SAFE_NO_PAD.encode(&key[1..33]),
+                y: URL_SAFE_NO_PAD.encode(&key[33..]),
+            });
+            (KeyAlgorithm::ES256, parameters)
+        }
+        (Algorithm::EdDSA, "PUBLIC KEY") => {
+            let key = subject_public_key(pem.contents()).ok_or_else(invalid)?;
+            if key.len() != 32 {
+                return Err(invalid());
+            }
+            let parameters = AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(&key),
+            });
+            (KeyAlgorithm::EdDSA, parameters)
+        }
+        _ => return Err(invalid()),
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    })
+}
+
+/// Extracts the key bits of a DER encoded `SubjectPublicKeyInfo`.
+fn subject_public_key(der: &[u8]) -> Option<Vec<u8>> {
+    match simple_asn1::from_der(der).ok()?.as_slice() {
+        [ASN1Block::Sequence(_, fields)] => match fields.as_slice() {
+            [ASN1Block::Sequence(..), ASN1Block::BitString(_, _, key)] => Some(key.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Reads modulus and exponent of a DER encoded PKCS#1 `RSAPublicKey`.
+fn rsa_parameters(der: &[u8]) -> Option<AlgorithmParameters> {
+    match simple_asn1::from_der(der).ok()?.as_slice() {
+        [ASN1Block::Sequence(_, fields)] => match fields.as_slice() {
+            [ASN1Block::Integer(_, n), ASN1Block::Integer(_, e)] => {
+                Some(AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(n.to_bytes_be().1),
+                    e: URL_SAFE_NO_PAD.encode(e.to_bytes_be().1),
+                }))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use lettre::{message::Mailbox, AsyncFileTransport, AsyncTransport, Tokio1Executor};
+
+use super::{build_message, Email, Mailer};
+use crate::error::AppError;
+
+/// Development transport that writes every email as an `.eml` file instead of sending it.
+pub struct FileMailer {
+    transport: AsyncFileTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl FileMailer {
+    pub fn new(dir: &str, from: Mailbox) -> Result<Self, AppError> {
+        std::fs::create_dir_all(dir).map_err(|err| {
+            tracing::error!("Failed to create mail directory {}: {:?}", dir, err);
+            AppError::InternalServerError
+        })?;
+        Ok(Self {
+            transport: AsyncFileTransport::new(dir),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: Email) -> Result<(), AppError> {
+        let message = build_message(&self.from, email)?;
+        let id = self.transport.send(message).await.map_err(|err| {
+            tracing::error!("Failed to write email file: {:?}", err);
+            AppError::InternalServerError
+        })?;
+        tracing::debug!("Email written to {}.eml", id);
+        Ok(())
+    }
+}
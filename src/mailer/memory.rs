@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+use super::{Email, Mailer};
+use crate::error::AppError;
+
+/// Keeps sent emails in memory so tests can inspect them.
+#[derive(Default)]
+pub struct MemoryMailer {
+    sent: Mutex<Vec<Email>>,
+}
+
+impl MemoryMailer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sent(&self) -> Vec<Email> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    pub fn last_sent_to(&self, to: &str) -> Option<Email> {
+        self.sent.lock().unwrap().iter().rev().find(|email| email.to == to).cloned()
+    }
+
+    pub fn clear(&self) {
+        self.sent.lock().unwrap().clear();
+    }
+}
+
+#[async_trait]
+impl Mailer for MemoryMailer {
+    async fn send(&self, email: Email) -> Result<(), AppError> {
+        self.sent.lock().unwrap().push(email);
+        Ok(())
+    }
+}
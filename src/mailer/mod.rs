@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use lettre::{message::Mailbox, Message};
+use std::sync::Arc;
+
+use crate::{
+    config::{MailTransport, CONFIG},
+    error::{AppError, ErrorCode},
+};
+
+pub mod file;
+pub mod memory;
+pub mod smtp;
+pub mod templates;
+
+pub use file::FileMailer;
+pub use memory::MemoryMailer;
+pub use smtp::SmtpMailer;
+
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), AppError>;
+}
+
+/// Builds the mailer selected by `MAIL_TRANSPORT`.
+pub fn create_mailer() -> Result<Arc<dyn Mailer>, AppError> {
+    let from = parse_from_address()?;
+    let mailer: Arc<dyn Mailer> = match CONFIG.mail_transport {
+        MailTransport::Smtp => Arc::new(SmtpMailer::new(from)?),
+        MailTransport::File => Arc::new(FileMailer::new(&CONFIG.mail_file_dir, from)?),
+        MailTransport::Memory => Arc::new(MemoryMailer::new()),
+    };
+    Ok(mailer)
+}
+
+fn parse_from_address() -> Result<Mailbox, AppError> {
+    CONFIG.email_from.parse().map_err(|err| {
+        tracing::error!("Invalid EMAIL_FROM address: {:?}", err);
+        AppError::InternalServerError
+    })
+}
+
+fn build_message(from: &Mailbox, email: Email) -> Result<Message, AppError> {
+    let to: Mailbox = email
+        .to
+        .parse()
+        .map_err(|_| AppError::BadRequest(ErrorCode::EmailInvalid.into()))?;
+    Message::builder()
+        .from(from.clone())
+        .to(to)
+        .subject(email.subject)
+        .body(email.body)
+        .map_err(|err| {
+            tracing::error!("Failed to build email: {:?}", err);
+            AppError::InternalServerError
+        })
+}
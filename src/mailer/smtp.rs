@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use lettre::{
+    message::Mailbox,
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Tokio1Executor,
+};
+
+use super::{build_message, Email, Mailer};
+use crate::{config::CONFIG, error::AppError};
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(from: Mailbox) -> Result<Self, AppError> {
+        let transport = match (&CONFIG.smtp_username, &CONFIG.smtp_password) {
+            (Some(username), Some(password)) => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&CONFIG.smtp_host)?
+                    .port(CONFIG.smtp_port)
+                    .credentials(Credentials::new(username.clone(), password.clone()))
+                    .build()
+            }
+            // Without credentials we assume a local development server (e.g. Mailpit)
+            _ => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&CONFIG.smtp_host)
+                .port(CONFIG.smtp_port)
+                .build(),
+        };
+        Ok(Self { transport, from })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: Email) -> Result<(), AppError> {
+        let message = build_message(&self.from, email)?;
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use super::Email;
+use crate::{
+    config::CONFIG,
+    i18n::{self, Locale},
+};
+
+/// Subject and body come from the `email.<name>` entries of the message catalog.
+fn render(name: &str, locale: Locale, to: &str, vars: &[(&str, &str)]) -> Email {
+    Email {
+        to: to.to_string(),
+        subject: i18n::text(locale, &format!("email.{}.subject", name), vars),
+        body: i18n::text(locale, &format!("email.{}.body", name), vars),
+    }
+}
+
+pub fn verification_email(to: &str, token: &str, locale: Locale) -> Email {
+    let url = format!("{}/verify-email?token={}", CONFIG.client_url, token);
+    render("verify_email", locale, to, &[("url", &url)])
+}
+
+pub fn reset_password_email(to: &str, token: &str, locale: Locale) -> Email {
+    let url = format!("{}/reset-password?token={}", CONFIG.client_url, token);
+    render("reset_password", locale, to, &[("url", &url)])
+}
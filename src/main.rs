@@ -1,7 +1,7 @@
 #![allow(dead_code)] 
 #![allow(unused_imports)]
 
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 use axum::Router;
 use sqlx::postgres::PgPoolOptions;
 use tokio::net::TcpListener;
@@ -11,10 +11,17 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 mod config;
 mod domain;
 mod error;
+mod extractor;
 mod handler;
+mod i18n;
+mod jwt;
+mod mailer;
 mod middleware;
+mod password;
+mod policy;
 mod repository;
 mod routes;
+mod totp;
 mod usecase;
 
 use config::CONFIG;
@@ -42,7 +49,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let listener = TcpListener::bind(format!("{}:{}", CONFIG.server_host, CONFIG.server_port)).await?;
     tracing::info!("Server listening on {}", listener.local_addr()?);
-    axum::serve(listener, app.layer(TraceLayer::new_for_http())).await?;
+    axum::serve(
+        listener,
+        app.layer(TraceLayer::new_for_http())
+            .into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
\ No newline at end of file
@@ -4,62 +4,160 @@ use axum::{
     middleware::Next,
     response::Response,
 };
-use jsonwebtoken::{decode, DecodingKey, Validation};
-use std::sync::Arc;
+use jsonwebtoken::errors::ErrorKind;
+use std::{future::Future, pin::Pin, sync::Arc};
+use uuid::Uuid;
 
 use crate::{
-    config::CONFIG,
     domain::{
-        token_model::TokenClaims,
-        user_model::{Role, User},
+        api_key_model::API_KEY_PREFIX,
+        token_model::{AuthContext, TokenClaims},
+        user_model::User,
+    },
+    error::{AppError, ErrorCode},
+    i18n::Locale,
+    jwt::JwtKeys,
+    repository::{
+        api_key_repository::ApiKeyRepository,
+        revoked_token_repository::RevokedTokenRepository,
+        user_repository::UserRepository,
     },
-    error::AppError,
-    repository::user_repository::UserRepository,
 };
 
+/// State of the `auth` middleware.
+#[derive(Clone)]
+pub struct AuthState {
+    pub user_repo: Arc<dyn UserRepository>,
+    pub revoked_token_repo: Arc<dyn RevokedTokenRepository>,
+    pub api_key_repo: Arc<dyn ApiKeyRepository>,
+    pub jwt_keys: Arc<JwtKeys>,
+}
+
+/// Header carrying an API key, as an alternative to `Authorization: Bearer pat_...`.
+const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Authenticates the request with an access token or an API key, and inserts
+/// the `User` and an `AuthContext` into the request extensions.
 pub async fn auth(
-    State(user_repo): State<Arc<dyn UserRepository>>,
+    State(state): State<AuthState>,
     mut req: Request,
     next: Next,
 ) -> Result<Response, AppError> {
-    let token = req
-        .headers()
+    let headers = req.headers();
+    let bearer = headers
         .get("Authorization")
         .and_then(|auth_header| auth_header.to_str().ok())
         .and_then(|auth_value| auth_value.strip_prefix("Bearer "));
+    let api_key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|header| header.to_str().ok());
+
+    let (user_id, context) = match (api_key, bearer) {
+        (Some(key), _) => authenticate_api_key(&state, key).await?,
+        (None, Some(token)) if token.starts_with(API_KEY_PREFIX) => authenticate_api_key(&state, token).await?,
+        (None, Some(token)) => authenticate_access_token(&state, token).await?,
+        (None, None) => return Err(AppError::Unauthorized(ErrorCode::AuthenticationRequired.into())),
+    };
+
+    let user = state.user_repo.find_by_id(user_id).await?.ok_or_else(|| {
+        AppError::NotFound(ErrorCode::UserNotFound.into())
+    })?;
 
-    let token = token.ok_or_else(|| AppError::Unauthorized("You are not logged in".to_string()))?;
+    // The user's saved language overrides Accept-Language, for the handler and
+    // (through the response) for the `locale` middleware
+    let preferred_locale = user.locale.as_deref().and_then(Locale::parse);
+    if let Some(locale) = preferred_locale {
+        req.extensions_mut().insert(locale);
+    }
 
-    let claims = decode::<TokenClaims>(
-        token,
-        &DecodingKey::from_secret(CONFIG.jwt_secret.as_ref()),
-        &Validation::default(),
-    )
-    .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?
-    .claims;
+    req.extensions_mut().insert(user);
+    req.extensions_mut().insert(context);
+    let mut response = next.run(req).await;
+    if let Some(locale) = preferred_locale {
+        response.extensions_mut().insert(locale);
+    }
+    Ok(response)
+}
+
+async fn authenticate_access_token(state: &AuthState, token: &str) -> Result<(Uuid, AuthContext), AppError> {
+    let claims: TokenClaims = state
+        .jwt_keys
+        .decode(token)
+        .map_err(|err| match err {
+            AppError::JwtError(err) if *err.kind() == ErrorKind::ExpiredSignature => {
+                AppError::Unauthorized(ErrorCode::TokenExpired.into())
+            }
+            _ => AppError::Unauthorized(ErrorCode::TokenInvalid.into()),
+        })?;
 
     if claims.token_type != "access" {
-        return Err(AppError::Unauthorized("Invalid token type".to_string()));
+        return Err(AppError::Unauthorized(ErrorCode::TokenInvalid.into()));
     }
 
-    let user = user_repo.find_by_id(claims.sub).await?.ok_or_else(|| {
-        AppError::NotFound("The user belonging to this token no longer exists".to_string())
-    })?;
+    if state.revoked_token_repo.is_revoked(claims.jti).await? {
+        return Err(AppError::Unauthorized(ErrorCode::TokenRevoked.into()));
+    }
 
-    req.extensions_mut().insert(user);
-    Ok(next.run(req).await)
+    Ok((claims.sub, AuthContext::from_claims(&claims)))
 }
 
-pub async fn admin_only(req: Request, next: Next) -> Result<Response, AppError> {
-    let user = req.extensions().get::<User>().ok_or_else(|| {
-        AppError::InternalServerError
-    })?;
+async fn authenticate_api_key(state: &AuthState, key: &str) -> Result<(Uuid, AuthContext), AppError> {
+    let api_key = state
+        .api_key_repo
+        .find_by_key(key)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized(ErrorCode::ApiKeyInvalid.into()))?;
+
+    if api_key.is_expired() {
+        return Err(AppError::Unauthorized(ErrorCode::ApiKeyExpired.into()));
+    }
+
+    state.api_key_repo.touch(api_key.id).await?;
+
+    let context = AuthContext { scopes: api_key.scopes, api_key_id: Some(api_key.id) };
+    Ok((api_key.user_id, context))
+}
 
-    if user.role >= Role::Admin {
-        Ok(next.run(req).await)
-    } else {
-        Err(AppError::Forbidden(
-            "You do not have permission to access this resource".to_string(),
-        ))
+type MiddlewareFuture = Pin<Box<dyn Future<Output = Result<Response, AppError>> + Send>>;
+
+/// Only lets the request through when the authenticated user's role grants `permission`.
+/// Must run after `auth`, e.g.
+/// `.route_layer(middleware::from_fn(require_permission(permissions::USERS_WRITE)))`.
+pub fn require_permission(
+    permission: &'static str,
+) -> impl Fn(Request, Next) -> MiddlewareFuture + Clone + Send + Sync + 'static {
+    move |req: Request, next: Next| {
+        Box::pin(async move {
+            let user = req.extensions().get::<User>().ok_or_else(|| {
+                AppError::InternalServerError
+            })?;
+
+            if user.has_permission(permission) {
+                Ok(next.run(req).await)
+            } else {
+                Err(AppError::Forbidden(ErrorCode::PermissionDenied.into()))
+            }
+        })
     }
-}
\ No newline at end of file
+}
+
+/// Only lets the request through when the access token or API key carries `scope`.
+/// Must run after `auth`, e.g.
+/// `.route_layer(middleware::from_fn(require_scope(scopes::USERS_READ)))`.
+pub fn require_scope(
+    scope: &'static str,
+) -> impl Fn(Request, Next) -> MiddlewareFuture + Clone + Send + Sync + 'static {
+    move |req: Request, next: Next| {
+        Box::pin(async move {
+            let context = req.extensions().get::<AuthContext>().ok_or_else(|| {
+                AppError::InternalServerError
+            })?;
+
+            if context.has_scope(scope) {
+                Ok(next.run(req).await)
+            } else {
+                Err(AppError::Forbidden(ErrorCode::ScopeMissing.with("scope", scope)))
+            }
+        })
+    }
+}
@@ -0,0 +1,34 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{error::ProblemDetails, i18n::Locale};
+
+/// Resolves the request's locale from `Accept-Language` and translates error
+/// responses into it. A signed-in user's saved preference wins: `auth` puts it
+/// into the request and reports it back on the response.
+///
+/// Must run inside `request_id`, which serializes the translated problem.
+pub async fn locale(mut req: Request, next: Next) -> Response {
+    let requested = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Locale::from_accept_language)
+        .unwrap_or_else(Locale::default_locale);
+    req.extensions_mut().insert(requested);
+
+    let mut response = next.run(req).await;
+
+    let locale = response.extensions().get::<Locale>().copied().unwrap_or(requested);
+    if let Some(problem) = response.extensions_mut().get_mut::<ProblemDetails>() {
+        problem.localize(locale);
+        response
+            .headers_mut()
+            .insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale.as_str()));
+    }
+    response
+}
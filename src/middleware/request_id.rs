@@ -0,0 +1,51 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+use crate::error::ProblemDetails;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Identifier of the current request, available as a request extension.
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
+
+/// Tags every request with an id, reusing a well-formed incoming `X-Request-Id`,
+/// and echoes it back in the response header. Error responses also get the
+/// request id and path written into their problem details, which are then
+/// serialized again so changes made by inner middleware (e.g. `locale`) show up.
+pub async fn request_id(mut req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_valid_request_id(value))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let path = req.uri().path().to_string();
+    req.extensions_mut().insert(RequestId(id.clone()));
+
+    let mut response = next.run(req).await;
+
+    if let Some(mut problem) = response.extensions_mut().remove::<ProblemDetails>() {
+        problem.instance = Some(path);
+        problem.request_id = Some(id.clone());
+        // Keep status and headers (e.g. Retry-After), swap in the completed body
+        let (mut parts, _) = response.into_parts();
+        parts.headers.remove(header::CONTENT_LENGTH);
+        response = Response::from_parts(parts, problem.into_response().into_body());
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty() && value.len() <= 128 && value.bytes().all(|b| b.is_ascii_graphic())
+}
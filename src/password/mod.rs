@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher as _, PasswordVerifier as _, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+use rand::rngs::OsRng;
+use serde::Deserialize;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Semaphore;
+
+use crate::{config::CONFIG, error::{AppError, ErrorCode}};
+
+pub mod policy;
+
+pub use policy::{create_password_policy, PasswordPolicy};
+
+/// Algorithm used for new password hashes.
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordHashAlgorithm {
+    #[default]
+    Argon2id,
+    Bcrypt,
+}
+
+/// Hashing and verifying are CPU-heavy; implementations must not run them on the async runtime.
+#[async_trait]
+pub trait PasswordHasher: Send + Sync {
+    async fn hash(&self, password: &str) -> Result<String, AppError>;
+    /// Checks `password` against a hash of any supported algorithm.
+    async fn verify(&self, password: &str, hash: &str) -> Result<bool, AppError>;
+    /// Whether `hash` was made with another algorithm or other parameters than
+    /// new hashes, so it should be replaced the next time the password is known.
+    fn needs_rehash(&self, hash: &str) -> bool;
+}
+
+/// Hashes with the configured algorithm, and verifies both Argon2 and bcrypt hashes.
+///
+/// The work runs on Tokio's blocking threads, at most `concurrency` at a time.
+/// Callers wait up to `queue_timeout` for a slot and then get a 503.
+pub struct PasswordHasherImpl {
+    hashing: Arc<Hashing>,
+    permits: Arc<Semaphore>,
+    queue_timeout: Duration,
+}
+
+impl PasswordHasherImpl {
+    pub fn new(hashing: Hashing, concurrency: usize, queue_timeout: Duration) -> Self {
+        Self {
+            hashing: Arc::new(hashing),
+            permits: Arc::new(Semaphore::new(concurrency)),
+            queue_timeout,
+        }
+    }
+
+    async fn run_blocking<T, F>(&self, work: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Hashing) -> Result<T, AppError> + Send + 'static,
+    {
+        let permit = tokio::time::timeout(self.queue_timeout, self.permits.clone().acquire_owned())
+            .await
+            .map_err(|_| {
+                tracing::warn!("Password hashing is saturated, rejecting request");
+                AppError::ServiceUnavailable {
+                    code: ErrorCode::ServerBusy,
+                    retry_after: 1,
+                }
+            })?
+            .map_err(|_| AppError::InternalServerError)?;
+
+        let hashing = self.hashing.clone();
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            work(&hashing)
+        })
+        .await
+        .map_err(|err| {
+            tracing::error!("Password hashing task failed: {:?}", err);
+            AppError::InternalServerError
+        })?
+    }
+}
+
+/// The algorithms and their parameters. Its methods block while hashing.
+pub struct Hashing {
+    algorithm: PasswordHashAlgorithm,
+    argon2: Argon2<'static>,
+    bcrypt_cost: u32,
+}
+
+impl Hashing {
+    pub fn new(algorithm: PasswordHashAlgorithm, argon2_params: Params, bcrypt_cost: u32) -> Self {
+        Self {
+            algorithm,
+            argon2: Argon2::new(Algorithm::Argon2id, Version::V0x13, argon2_params),
+            bcrypt_cost,
+        }
+    }
+
+    fn hash(&self, password: &str) -> Result<String, AppError> {
+        match self.algorithm {
+            PasswordHashAlgorithm::Argon2id => {
+                let salt = SaltString::generate(&mut OsRng);
+                let hash = self.argon2.hash_password(password.as_bytes(), &salt).map_err(hash_error)?;
+                Ok(hash.to_string())
+            }
+            PasswordHashAlgorithm::Bcrypt => Ok(bcrypt::hash(password, self.bcrypt_cost)?),
+        }
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, AppError> {
+        if is_bcrypt_hash(hash) {
+            return Ok(bcrypt::verify(password, hash)?);
+        }
+        let parsed = PasswordHash::new(hash).map_err(hash_error)?;
+        match self.argon2.verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => Ok(true),
+            Err(argon2::password_hash::Error::Password) => Ok(false),
+            Err(err) => Err(hash_error(err)),
+        }
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        match self.algorithm {
+            PasswordHashAlgorithm::Argon2id => self.argon2_needs_rehash(hash),
+            PasswordHashAlgorithm::Bcrypt => !is_bcrypt_hash(hash) || bcrypt_cost(hash) != Some(self.bcrypt_cost),
+        }
+    }
+
+    fn argon2_needs_rehash(&self, hash: &str) -> bool {
+        let Ok(parsed) = PasswordHash::new(hash) else {
+            return true;
+        };
+        let Ok(params) = Params::try_from(&parsed) else {
+            return true;
+        };
+        let current = self.argon2.params();
+        parsed.algorithm != Algorithm::Argon2id.ident()
+            || parsed.version != Some(Version::V0x13.into())
+            || params.m_cost() != current.m_cost()
+            || params.t_cost() != current.t_cost()
+            || params.p_cost() != current.p_cost()
+    }
+}
+
+fn is_bcrypt_hash(hash: &str) -> bool {
+    ["$2a$", "$2b$", "$2x$", "$2y$"].iter().any(|prefix| hash.starts_with(prefix))
+}
+
+/// Cost factor of a bcrypt hash, e.g. 12 for `$2b$12$...`.
+fn bcrypt_cost(hash: &str) -> Option<u32> {
+    hash.split('$').nth(2)?.parse().ok()
+}
+
+fn hash_error(err: argon2::password_hash::Error) -> AppError {
+    tracing::error!("Password hashing error: {}", err);
+    AppError::InternalServerError
+}
+
+#[async_trait]
+impl PasswordHasher for PasswordHasherImpl {
+    async fn hash(&self, password: &str) -> Result<String, AppError> {
+        let password = password.to_string();
+        self.run_blocking(move |hashing| hashing.hash(&password)).await
+    }
+
+    async fn verify(&self, password: &str, hash: &str) -> Result<bool, AppError> {
+        let (password, hash) = (password.to_string(), hash.to_string());
+        self.run_blocking(move |hashing| hashing.verify(&password, &hash)).await
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        self.hashing.needs_rehash(hash)
+    }
+}
+
+/// Builds the password hasher from `PASSWORD_HASH_ALGORITHM`, its parameters and the concurrency limit.
+pub fn create_password_hasher() -> Result<Arc<dyn PasswordHasher>, AppError> {
+    let argon2_params = Params::new(
+        CONFIG.argon2_memory_cost,
+        CONFIG.argon2_time_cost,
+        CONFIG.argon2_parallelism,
+        None,
+    )
+    .map_err(|err| {
+        tracing::error!("Invalid Argon2 parameters: {}", err);
+        AppError::InternalServerError
+    })?;
+
+    if !(4..=31).contains(&CONFIG.bcrypt_cost) {
+        tracing::error!("Invalid BCRYPT_COST {}, expected 4 to 31", CONFIG.bcrypt_cost);
+        return Err(AppError::InternalServerError);
+    }
+
+    if CONFIG.password_hash_concurrency == 0 {
+        tracing::error!("PASSWORD_HASH_CONCURRENCY must be at least 1");
+        return Err(AppError::InternalServerError);
+    }
+
+    let queue_timeout = humantime::parse_duration(&CONFIG.password_hash_queue_timeout).map_err(|err| {
+        tracing::error!("Invalid PASSWORD_HASH_QUEUE_TIMEOUT: {}", err);
+        AppError::InternalServerError
+    })?;
+
+    let hashing = Hashing::new(CONFIG.password_hash_algorithm, argon2_params, CONFIG.bcrypt_cost);
+    Ok(Arc::new(PasswordHasherImpl::new(hashing, CONFIG.password_hash_concurrency, queue_timeout)))
+}
@@ -0,0 +1,239 @@
+use std::{collections::HashSet, fs, sync::Arc};
+use validator::{ValidationError, ValidationErrors};
+
+use crate::{config::CONFIG, error::AppError};
+
+/// Rules every new password has to satisfy, wherever it is set.
+pub struct PasswordPolicy {
+    min_length: usize,
+    min_character_classes: usize,
+    min_entropy_bits: f64,
+    /// Lowercased passwords that are rejected outright.
+    blocklist: HashSet<String>,
+}
+
+impl PasswordPolicy {
+    pub fn new(min_length: usize, min_character_classes: usize, min_entropy_bits: f64, blocklist: HashSet<String>) -> Self {
+        Self { min_length, min_character_classes, min_entropy_bits, blocklist }
+    }
+
+    /// Checks `password`, reporting violations as validation errors of `field`.
+    /// `personal_info` (the user's name and email) must not appear in the password.
+    pub fn validate(&self, field: &'static str, password: &str, personal_info: &[&str]) -> Result<(), AppError> {
+        let violations = self.violations(password, personal_info);
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        let mut errors = ValidationErrors::new();
+        for violation in violations {
+            errors.add(field, violation);
+        }
+        Err(errors.into())
+    }
+
+    fn violations(&self, password: &str, personal_info: &[&str]) -> Vec<ValidationError> {
+        let mut violations = Vec::new();
+        let lowercase = password.to_lowercase();
+
+        if password.chars().count() < self.min_length {
+            let mut violation = ValidationError::new("password_too_short");
+            violation.add_param("min".into(), &self.min_length);
+            violations.push(violation);
+        }
+        if character_classes(password) < self.min_character_classes {
+            let mut violation = ValidationError::new("password_character_classes");
+            violation.add_param("min".into(), &self.min_character_classes);
+            violations.push(violation);
+        }
+        if entropy_bits(password) < self.min_entropy_bits {
+            violations.push(ValidationError::new("password_too_weak"));
+        }
+        if personal_terms(personal_info).iter().any(|term| lowercase.contains(term.as_str())) {
+            violations.push(ValidationError::new("password_contains_personal_info"));
+        }
+        if self.blocklist.contains(&lowercase) {
+            violations.push(ValidationError::new("password_blocklisted"));
+        }
+
+        violations
+    }
+}
+
+/// Number of classes among lowercase, uppercase, digits and everything else.
+fn character_classes(password: &str) -> usize {
+    [
+        password.chars().any(|c| c.is_lowercase()),
+        password.chars().any(|c| c.is_uppercase()),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password.chars().any(|c| !c.is_alphanumeric()),
+    ]
+    .iter()
+    .filter(|&&present| present)
+    .count()
+}
+
+/// Rough brute-force entropy: the size of the character pool used, raised to the
+/// password length. Characters repeating the previous one or continuing a run
+/// like `abc` or `321` don't count towards the length.
+fn entropy_bits(password: &str) -> f64 {
+    let mut pool = 0;
+    if password.chars().any(|c| c.is_ascii_lowercase()) {
+        pool += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_uppercase()) {
+        pool += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_digit()) {
+        pool += 10;
+    }
+    if password.chars().any(|c| c.is_ascii() && !c.is_ascii_alphanumeric()) {
+        pool += 33;
+    }
+    if !password.is_ascii() {
+        pool += 100;
+    }
+    if pool == 0 {
+        return 0.0;
+    }
+
+    let chars: Vec<char> = password.chars().collect();
+    let effective_length = chars
+        .iter()
+        .enumerate()
+        .filter(|(i, &c)| {
+            *i == 0 || {
+                let step = c as i64 - chars[i - 1] as i64;
+                !(-1..=1).contains(&step)
+            }
+        })
+        .count();
+
+    effective_length as f64 * f64::from(pool).log2()
+}
+
+/// Lowercased words of the name and the email local part, long enough to matter.
+/// `John Doe` and `john.doe99@example.com` both give `john` and `doe`.
+fn personal_terms(personal_info: &[&str]) -> Vec<String> {
+    personal_info
+        .iter()
+        .flat_map(|info| {
+            let info = info.to_lowercase();
+            let text = info.split_once('@').map_or(info.as_str(), |(local_part, _)| local_part);
+            text.split(|c: char| !c.is_alphabetic())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .filter(|term| term.chars().count() >= 3)
+        .collect()
+}
+
+/// Builds the policy from the `PASSWORD_*` settings, loading the blocklist file if configured.
+pub fn create_password_policy() -> Result<Arc<PasswordPolicy>, AppError> {
+    let blocklist = match &CONFIG.password_blocklist_path {
+        Some(path) => load_blocklist(path)?,
+        None => HashSet::new(),
+    };
+
+    Ok(Arc::new(PasswordPolicy::new(
+        CONFIG.password_min_length,
+        CONFIG.password_min_character_classes,
+        CONFIG.password_min_entropy_bits,
+        blocklist,
+    )))
+}
+
+/// One password per line; blank lines and lines starting with `#` are ignored.
+fn load_blocklist(path: &str) -> Result<HashSet<String>, AppError> {
+    let content = fs::read_to_string(path).map_err(|err| {
+        tracing::error!("Failed to read PASSWORD_BLOCKLIST_PATH {}: {}", path, err);
+        AppError::InternalServerError
+    })?;
+
+    let blocklist: HashSet<String> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+        .collect();
+
+    tracing::info!("Loaded {} blocklisted passwords from {}", blocklist.len(), path);
+    Ok(blocklist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy::new(8, 2, 36.0, HashSet::from(["correcthorse1".to_string()]))
+    }
+
+    fn codes(password: &str, personal_info: &[&str]) -> Vec<String> {
+        policy()
+            .violations(password, personal_info)
+            .into_iter()
+            .map(|violation| violation.code.into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_strong_password() {
+        assert!(codes("Vq8#mZt2Lp", &["John Doe", "john.doe@example.com"]).is_empty());
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        assert_eq!(codes("", &[]), ["password_too_short", "password_character_classes", "password_too_weak"]);
+    }
+
+    #[test]
+    fn too_short_carries_the_minimum() {
+        let violations = policy().violations("Ab1!", &[]);
+        assert_eq!(violations[0].code, "password_too_short");
+        assert_eq!(violations[0].params["min"], 8);
+    }
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        assert!(!codes("äöüßéèàç", &[]).contains(&"password_too_short".to_string()));
+    }
+
+    #[test]
+    fn requires_character_classes() {
+        assert_eq!(character_classes("lowercaseonly"), 1);
+        assert_eq!(character_classes("Mixed Case 123"), 4);
+        assert!(codes("qwzxmvbnkjhg", &[]).contains(&"password_character_classes".to_string()));
+    }
+
+    #[test]
+    fn repeats_and_runs_add_no_entropy() {
+        let one_char = f64::from(26).log2();
+        assert_eq!(entropy_bits(""), 0.0);
+        assert_eq!(entropy_bits("aaaaaaaaaaaa"), one_char);
+        assert_eq!(entropy_bits("abcdefghijkl"), one_char);
+        assert_eq!(entropy_bits("zyxwvutsrqpo"), one_char);
+        assert_eq!(entropy_bits("azbycx"), 6.0 * one_char);
+    }
+
+    #[test]
+    fn rejects_low_entropy_passwords_that_pass_the_other_rules() {
+        assert_eq!(codes("Aaaaaaaa1111", &[]), ["password_too_weak"]);
+        assert_eq!(codes("abcdefgh12345", &[]), ["password_too_weak"]);
+    }
+
+    #[test]
+    fn rejects_the_name_and_email_local_part() {
+        let personal_info = ["John Doe", "jdoe99@example.com"];
+        assert_eq!(codes("Vq8#John!2Lp", &personal_info), ["password_contains_personal_info"]);
+        assert_eq!(codes("Vq8#JDOE!2Lp", &personal_info), ["password_contains_personal_info"]);
+        // The email domain and words shorter than 3 characters don't count
+        assert!(codes("Vq8#Example!2Lp", &personal_info).is_empty());
+        assert!(codes("Vq8#mZt2Lp", &["Al Bo"]).is_empty());
+    }
+
+    #[test]
+    fn rejects_blocklisted_passwords_case_insensitively() {
+        assert_eq!(codes("CorrectHorse1", &[]), ["password_blocklisted"]);
+    }
+}
@@ -0,0 +1,20 @@
+use uuid::Uuid;
+
+use super::{is_self_or, Action, Policy};
+use crate::domain::{role_model::permissions, user_model::User};
+
+/// The API keys of the user identified by `user_id`.
+pub struct ApiKeyResource {
+    pub user_id: Uuid,
+}
+
+impl Policy for ApiKeyResource {
+    fn allows(&self, actor: &User, action: Action) -> bool {
+        match action {
+            Action::Read | Action::List => is_self_or(actor, self.user_id, permissions::API_KEYS_READ),
+            Action::Create | Action::Update | Action::Delete => {
+                is_self_or(actor, self.user_id, permissions::API_KEYS_WRITE)
+            }
+        }
+    }
+}
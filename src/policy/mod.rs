@@ -0,0 +1,56 @@
+use uuid::Uuid;
+
+use crate::{domain::user_model::User, error::{AppError, ErrorCode}};
+
+pub mod api_key_policy;
+pub mod session_policy;
+pub mod user_policy;
+
+pub use api_key_policy::ApiKeyResource;
+pub use session_policy::SessionResource;
+pub use user_policy::UserResource;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Create,
+    Read,
+    List,
+    Update,
+    Delete,
+}
+
+/// Authorization rules for a resource. Implement this for every resource that
+/// handlers need to protect.
+pub trait Policy {
+    fn allows(&self, actor: &User, action: Action) -> bool;
+}
+
+pub fn can<R: Policy>(actor: &User, action: Action, resource: &R) -> bool {
+    resource.allows(actor, action)
+}
+
+/// Like [`can`], but turns a denial into `AppError::Forbidden`.
+pub fn authorize<R: Policy>(actor: &User, action: Action, resource: &R) -> Result<(), AppError> {
+    if can(actor, action, resource) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(ErrorCode::PermissionDenied.into()))
+    }
+}
+
+// --- Reusable rules ---
+
+pub fn has_permission(actor: &User, permission: &str) -> bool {
+    actor.has_permission(permission)
+}
+
+/// The actor holds every one of `permissions`, so granting them, or managing an
+/// account that has them, gives nobody more than the actor already has.
+pub fn holds_all(actor: &User, permissions: &[String]) -> bool {
+    permissions.iter().all(|permission| has_permission(actor, permission))
+}
+
+/// The actor owns the resource, or holds `permission` for everyone's resources.
+pub fn is_self_or(actor: &User, owner_id: Uuid, permission: &str) -> bool {
+    actor.id == owner_id || has_permission(actor, permission)
+}
@@ -0,0 +1,19 @@
+use uuid::Uuid;
+
+use super::{has_permission, is_self_or, Action, Policy};
+use crate::domain::{role_model::permissions, user_model::User};
+
+/// The sessions of the user identified by `user_id`.
+pub struct SessionResource {
+    pub user_id: Uuid,
+}
+
+impl Policy for SessionResource {
+    fn allows(&self, actor: &User, action: Action) -> bool {
+        match action {
+            Action::Read | Action::List => is_self_or(actor, self.user_id, permissions::SESSIONS_READ),
+            Action::Delete => is_self_or(actor, self.user_id, permissions::SESSIONS_WRITE),
+            Action::Create | Action::Update => has_permission(actor, permissions::SESSIONS_WRITE),
+        }
+    }
+}
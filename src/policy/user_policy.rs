@@ -0,0 +1,20 @@
+use uuid::Uuid;
+
+use super::{has_permission, is_self_or, Action, Policy};
+use crate::domain::{role_model::permissions, user_model::User};
+
+/// A user account addressed by id.
+pub struct UserResource {
+    pub id: Uuid,
+}
+
+impl Policy for UserResource {
+    fn allows(&self, actor: &User, action: Action) -> bool {
+        match action {
+            Action::Read => is_self_or(actor, self.id, permissions::USERS_READ),
+            Action::List => has_permission(actor, permissions::USERS_READ),
+            // Users manage their own account through /v1/users/me, which has extra checks
+            Action::Create | Action::Update | Action::Delete => has_permission(actor, permissions::USERS_WRITE),
+        }
+    }
+}
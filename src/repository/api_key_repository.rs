@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::{domain::api_key_model::ApiKey, error::AppError, repository::token_repository::hash_token};
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    /// Stores the hash of `key`; the key itself is never persisted.
+    async fn create(&self, user_id: Uuid, name: &str, prefix: &str, key: &str, scopes: &[String], expires_at: Option<DateTime<Utc>>) -> Result<ApiKey, AppError>;
+    async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<ApiKey>, AppError>;
+    async fn find_by_key(&self, key: &str) -> Result<Option<ApiKey>, AppError>;
+    /// Records that the key was used. Writes at most once a minute per key.
+    async fn touch(&self, id: Uuid) -> Result<(), AppError>;
+    /// Deletes a key of the given user. Returns `false` if there was no such key.
+    async fn delete_user_key(&self, user_id: Uuid, id: Uuid) -> Result<bool, AppError>;
+}
+
+pub struct ApiKeyRepositoryImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl ApiKeyRepositoryImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for ApiKeyRepositoryImpl {
+    async fn create(&self, user_id: Uuid, name: &str, prefix: &str, key: &str, scopes: &[String], expires_at: Option<DateTime<Utc>>) -> Result<ApiKey, AppError> {
+        sqlx::query_as!(
+            ApiKey,
+            r#"
+            INSERT INTO api_keys (user_id, name, prefix, key_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, name, prefix, scopes, expires_at, last_used_at, created_at
+            "#,
+            user_id, name, prefix, hash_token(key), scopes, expires_at
+        )
+        .fetch_one(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<ApiKey>, AppError> {
+        sqlx::query_as!(
+            ApiKey,
+            r#"
+            SELECT id, user_id, name, prefix, scopes, expires_at, last_used_at, created_at
+            FROM api_keys WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn find_by_key(&self, key: &str) -> Result<Option<ApiKey>, AppError> {
+        sqlx::query_as!(
+            ApiKey,
+            r#"
+            SELECT id, user_id, name, prefix, scopes, expires_at, last_used_at, created_at
+            FROM api_keys WHERE key_hash = $1
+            "#,
+            hash_token(key)
+        )
+        .fetch_optional(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn touch(&self, id: Uuid) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            UPDATE api_keys SET last_used_at = NOW()
+            WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')
+            "#,
+            id
+        )
+        .execute(&*self.db_pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_user_key(&self, user_id: Uuid, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query!("DELETE FROM api_keys WHERE user_id = $1 AND id = $2", user_id, id)
+            .execute(&*self.db_pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
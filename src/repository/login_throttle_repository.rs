@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+use chrono::{DateTime, Utc};
+
+use crate::error::AppError;
+
+/// What failed login attempts are counted against.
+pub enum LoginThrottleKey<'a> {
+    /// An account, by email, so that unknown emails are throttled like known ones.
+    Account(&'a str),
+    Ip(&'a str),
+}
+
+impl LoginThrottleKey<'_> {
+    fn parts(&self) -> (&'static str, String) {
+        match self {
+            LoginThrottleKey::Account(email) => ("account", email.to_lowercase()),
+            LoginThrottleKey::Ip(ip) => ("ip", ip.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct LoginThrottle {
+    /// Failures since the last lockout.
+    pub failed_attempts: i32,
+    /// Lockouts in a row, used for the exponential backoff.
+    pub lockouts: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait LoginThrottleRepository: Send + Sync {
+    /// Returns the end of the current lockout, if the subject is locked.
+    async fn find_locked_until(&self, key: &LoginThrottleKey<'_>) -> Result<Option<DateTime<Utc>>, AppError>;
+    /// Counts a failed attempt. Counters are started over when the previous
+    /// failure is older than `forget_after`.
+    async fn record_failure(&self, key: &LoginThrottleKey<'_>, forget_after: Duration) -> Result<LoginThrottle, AppError>;
+    /// Locks the subject until `locked_until` and starts counting failures again.
+    async fn lock(&self, key: &LoginThrottleKey<'_>, locked_until: DateTime<Utc>) -> Result<(), AppError>;
+    /// Forgets every failure and lockout. Returns `false` if there was nothing to forget.
+    async fn reset(&self, key: &LoginThrottleKey<'_>) -> Result<bool, AppError>;
+    /// Removes unlocked entries without failures in `forget_after`. Returns the number of removed entries.
+    async fn delete_stale(&self, forget_after: Duration) -> Result<u64, AppError>;
+}
+
+pub struct LoginThrottleRepositoryImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl LoginThrottleRepositoryImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl LoginThrottleRepository for LoginThrottleRepositoryImpl {
+    async fn find_locked_until(&self, key: &LoginThrottleKey<'_>) -> Result<Option<DateTime<Utc>>, AppError> {
+        let (kind, subject) = key.parts();
+        let locked_until = sqlx::query_scalar!(
+            r#"
+            SELECT locked_until AS "locked_until!" FROM login_throttles
+            WHERE kind = $1 AND subject = $2 AND locked_until > NOW()
+            "#,
+            kind, subject
+        )
+        .fetch_optional(&*self.db_pool)
+        .await?;
+        Ok(locked_until)
+    }
+
+    async fn record_failure(&self, key: &LoginThrottleKey<'_>, forget_after: Duration) -> Result<LoginThrottle, AppError> {
+        let (kind, subject) = key.parts();
+        sqlx::query_as!(
+            LoginThrottle,
+            r#"
+            INSERT INTO login_throttles (kind, subject, failed_attempts)
+            VALUES ($1, $2, 1)
+            ON CONFLICT (kind, subject) DO UPDATE SET
+                failed_attempts = CASE WHEN login_throttles.last_failed_at < NOW() - make_interval(secs => $3)
+                    THEN 1 ELSE login_throttles.failed_attempts + 1 END,
+                lockouts = CASE WHEN login_throttles.last_failed_at < NOW() - make_interval(secs => $3)
+                    THEN 0 ELSE login_throttles.lockouts END,
+                last_failed_at = NOW()
+            RETURNING failed_attempts, lockouts, locked_until
+            "#,
+            kind, subject, forget_after.as_secs_f64()
+        )
+        .fetch_one(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn lock(&self, key: &LoginThrottleKey<'_>, locked_until: DateTime<Utc>) -> Result<(), AppError> {
+        let (kind, subject) = key.parts();
+        sqlx::query!(
+            r#"
+            UPDATE login_throttles SET failed_attempts = 0, lockouts = lockouts + 1, locked_until = $3
+            WHERE kind = $1 AND subject = $2
+            "#,
+            kind, subject, locked_until
+        )
+        .execute(&*self.db_pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn reset(&self, key: &LoginThrottleKey<'_>) -> Result<bool, AppError> {
+        let (kind, subject) = key.parts();
+        let result = sqlx::query!("DELETE FROM login_throttles WHERE kind = $1 AND subject = $2", kind, subject)
+            .execute(&*self.db_pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_stale(&self, forget_after: Duration) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM login_throttles
+            WHERE last_failed_at < NOW() - make_interval(secs => $1)
+              AND (locked_until IS NULL OR locked_until < NOW())
+            "#,
+            forget_after.as_secs_f64()
+        )
+        .execute(&*self.db_pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,2 +1,8 @@
 pub mod user_repository;
-pub mod token_repository;
\ No newline at end of file
+pub mod token_repository;
+pub mod role_repository;
+pub mod security_event_repository;
+pub mod revoked_token_repository;
+pub mod two_factor_repository;
+pub mod api_key_repository;
+pub mod login_throttle_repository;
@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// Denylist of access tokens, keyed by their `jti` claim.
+///
+/// The `revoke_*` methods denylist the access tokens issued together with the
+/// refresh tokens of a session, so they must run before those refresh tokens are deleted.
+#[async_trait]
+pub trait RevokedTokenRepository: Send + Sync {
+    async fn revoke_family(&self, user_id: Uuid, family_id: Uuid) -> Result<(), AppError>;
+    /// Revokes every session of a user, except `keep_family_id` if given.
+    async fn revoke_user_families(&self, user_id: Uuid, keep_family_id: Option<Uuid>) -> Result<(), AppError>;
+    async fn is_revoked(&self, jti: Uuid) -> Result<bool, AppError>;
+    /// Removes entries whose token has expired anyway. Returns the number of removed entries.
+    async fn delete_expired(&self) -> Result<u64, AppError>;
+}
+
+pub struct RevokedTokenRepositoryImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl RevokedTokenRepositoryImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl RevokedTokenRepository for RevokedTokenRepositoryImpl {
+    async fn revoke_family(&self, user_id: Uuid, family_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO revoked_access_tokens (jti, user_id, expires_at)
+            SELECT access_jti, user_id, access_expires_at FROM tokens
+            WHERE user_id = $1 AND family_id = $2 AND access_jti IS NOT NULL AND access_expires_at > NOW()
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(family_id)
+        .execute(&*self.db_pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn revoke_user_families(&self, user_id: Uuid, keep_family_id: Option<Uuid>) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO revoked_access_tokens (jti, user_id, expires_at)
+            SELECT access_jti, user_id, access_expires_at FROM tokens
+            WHERE user_id = $1 AND family_id IS DISTINCT FROM $2
+                AND access_jti IS NOT NULL AND access_expires_at > NOW()
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(keep_family_id)
+        .execute(&*self.db_pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn is_revoked(&self, jti: Uuid) -> Result<bool, AppError> {
+        let revoked: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM revoked_access_tokens WHERE jti = $1)")
+            .bind(jti)
+            .fetch_one(&*self.db_pool)
+            .await?;
+        Ok(revoked)
+    }
+
+    async fn delete_expired(&self) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM revoked_access_tokens WHERE expires_at <= NOW()")
+            .execute(&*self.db_pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,154 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    domain::role_model::{Permission, Role},
+    error::{AppError, ErrorCode},
+};
+
+#[async_trait]
+pub trait RoleRepository: Send + Sync {
+    async fn find_all(&self) -> Result<Vec<Role>, AppError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Role>, AppError>;
+    async fn find_by_name(&self, name: &str) -> Result<Option<Role>, AppError>;
+    async fn create(&self, name: &str, description: &str, permissions: &[String]) -> Result<Role, AppError>;
+    async fn update_by_id(&self, id: Uuid, description: Option<String>, permissions: Option<Vec<String>>) -> Result<Role, AppError>;
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), AppError>;
+    async fn count_users(&self, role_name: &str) -> Result<i64, AppError>;
+    async fn find_all_permissions(&self) -> Result<Vec<Permission>, AppError>;
+}
+
+pub struct RoleRepositoryImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl RoleRepositoryImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+
+    /// Replaces every permission of a role inside the given transaction.
+    async fn set_permissions(tx: &mut Transaction<'_, Postgres>, role_id: Uuid, permissions: &[String]) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM role_permissions WHERE role_id = $1")
+            .bind(role_id)
+            .execute(&mut **tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO role_permissions (role_id, permission_id)
+            SELECT $1, id FROM permissions WHERE name = ANY($2)
+            "#,
+        )
+        .bind(role_id)
+        .bind(permissions)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RoleRepository for RoleRepositoryImpl {
+    async fn find_all(&self) -> Result<Vec<Role>, AppError> {
+        sqlx::query_as!(
+            Role,
+            r#"
+            SELECT id, name, description, is_system, role_permission_names(name) AS "permissions!", created_at, updated_at
+            FROM roles ORDER BY name
+            "#
+        )
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Role>, AppError> {
+        sqlx::query_as!(
+            Role,
+            r#"
+            SELECT id, name, description, is_system, role_permission_names(name) AS "permissions!", created_at, updated_at
+            FROM roles WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Role>, AppError> {
+        sqlx::query_as!(
+            Role,
+            r#"
+            SELECT id, name, description, is_system, role_permission_names(name) AS "permissions!", created_at, updated_at
+            FROM roles WHERE name = $1
+            "#,
+            name
+        )
+        .fetch_optional(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn create(&self, name: &str, description: &str, permissions: &[String]) -> Result<Role, AppError> {
+        let mut tx = self.db_pool.begin().await?;
+        let role_id: Uuid = sqlx::query_scalar("INSERT INTO roles (name, description) VALUES ($1, $2) RETURNING id")
+            .bind(name)
+            .bind(description)
+            .fetch_one(&mut *tx)
+            .await?;
+        Self::set_permissions(&mut tx, role_id, permissions).await?;
+        tx.commit().await?;
+
+        self.find_by_id(role_id).await?.ok_or(AppError::InternalServerError)
+    }
+
+    async fn update_by_id(&self, id: Uuid, description: Option<String>, permissions: Option<Vec<String>>) -> Result<Role, AppError> {
+        let mut tx = self.db_pool.begin().await?;
+        let result = sqlx::query("UPDATE roles SET description = COALESCE($1, description), updated_at = NOW() WHERE id = $2")
+            .bind(description)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(ErrorCode::RoleNotFound.into()));
+        }
+        if let Some(permissions) = permissions {
+            Self::set_permissions(&mut tx, id, &permissions).await?;
+        }
+        tx.commit().await?;
+
+        self.find_by_id(id).await?.ok_or_else(|| AppError::NotFound(ErrorCode::RoleNotFound.into()))
+    }
+
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM roles WHERE id = $1")
+            .bind(id)
+            .execute(&*self.db_pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(ErrorCode::RoleNotFound.into()));
+        }
+        Ok(())
+    }
+
+    async fn count_users(&self, role_name: &str) -> Result<i64, AppError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE role = $1")
+            .bind(role_name)
+            .fetch_one(&*self.db_pool)
+            .await?;
+        Ok(count)
+    }
+
+    async fn find_all_permissions(&self) -> Result<Vec<Permission>, AppError> {
+        sqlx::query_as!(
+            Permission,
+            "SELECT id, name, description, created_at FROM permissions ORDER BY name"
+        )
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+}
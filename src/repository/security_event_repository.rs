@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::error::AppError;
+
+/// Event types written to `security_events`.
+pub mod event_types {
+    pub const REFRESH_TOKEN_REUSE: &str = "refresh_token_reuse";
+    pub const TWO_FACTOR_ENABLED: &str = "two_factor_enabled";
+    pub const TWO_FACTOR_DISABLED: &str = "two_factor_disabled";
+    pub const RECOVERY_CODE_USED: &str = "recovery_code_used";
+    pub const API_KEY_CREATED: &str = "api_key_created";
+    pub const API_KEY_REVOKED: &str = "api_key_revoked";
+    pub const ACCOUNT_LOCKED: &str = "account_locked";
+    pub const ACCOUNT_UNLOCKED: &str = "account_unlocked";
+    pub const EMAIL_CHANGED: &str = "email_changed";
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct SecurityEvent {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub event_type: String,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait SecurityEventRepository: Send + Sync {
+    async fn create(&self, user_id: Option<Uuid>, event_type: &str, description: &str) -> Result<SecurityEvent, AppError>;
+}
+
+pub struct SecurityEventRepositoryImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl SecurityEventRepositoryImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl SecurityEventRepository for SecurityEventRepositoryImpl {
+    async fn create(&self, user_id: Option<Uuid>, event_type: &str, description: &str) -> Result<SecurityEvent, AppError> {
+        sqlx::query_as!(
+            SecurityEvent,
+            r#"
+            INSERT INTO security_events (user_id, event_type, description)
+            VALUES ($1, $2, $3)
+            RETURNING id, user_id, event_type, description, created_at
+            "#,
+            user_id, event_type, description
+        )
+        .fetch_one(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+}
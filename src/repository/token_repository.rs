@@ -3,24 +3,62 @@ use sqlx::PgPool;
 use std::sync::Arc;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use crate::{domain::token_model::TokenType, error::AppError};
+use sha2::{Digest, Sha256};
+use crate::{
+    domain::{
+        session_model::{ClientInfo, Session},
+        token_model::TokenType,
+    },
+    error::AppError,
+};
 
 #[derive(Debug, sqlx::FromRow)]
 pub struct Token {
     pub id: Uuid,
-    pub token: String,
+    /// Hex encoded SHA-256 digest of the token. The token itself is never stored.
+    pub token_hash: String,
     pub user_id: Uuid,
     pub token_type: TokenType,
     pub expires_at: DateTime<Utc>,
     pub blacklisted: bool,
+    /// Refresh tokens issued from the same login share a family.
+    pub family_id: Option<Uuid>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// The access token issued together with this refresh token.
+    pub access_jti: Option<Uuid>,
+    pub access_expires_at: Option<DateTime<Utc>>,
+}
+
+/// Identifies the access token issued together with a refresh token.
+pub struct AccessTokenRef {
+    pub jti: Uuid,
+    pub expires_at: DateTime<Utc>,
 }
 
 #[async_trait]
 pub trait TokenRepository: Send + Sync {
     async fn create(&self, token: &str, user_id: Uuid, expires_at: DateTime<Utc>, token_type: TokenType) -> Result<Token, AppError>;
+    async fn create_in_family(&self, token: &str, user_id: Uuid, expires_at: DateTime<Utc>, family_id: Uuid, access: &AccessTokenRef, client: &ClientInfo) -> Result<Token, AppError>;
     async fn find_by_token(&self, token: &str) -> Result<Option<Token>, AppError>;
+    /// Like `find_by_token`, but also returns blacklisted (already consumed) tokens.
+    async fn find_by_token_with_blacklisted(&self, token: &str) -> Result<Option<Token>, AppError>;
+    /// Marks a token as consumed. Returns `false` if it was already blacklisted.
+    async fn blacklist(&self, id: Uuid) -> Result<bool, AppError>;
+    async fn delete_family(&self, family_id: Uuid) -> Result<(), AppError>;
+    /// Active sessions of a user, i.e. the unconsumed refresh token of every family.
+    async fn find_sessions_by_user(&self, user_id: Uuid) -> Result<Vec<Session>, AppError>;
+    /// Deletes a family only if it belongs to `user_id`. Returns `false` if nothing was deleted.
+    async fn delete_user_family(&self, user_id: Uuid, family_id: Uuid) -> Result<bool, AppError>;
     async fn delete(&self, id: Uuid) -> Result<(), AppError>;
+    /// Counts a wrong code entered for the token. Returns the number of failed attempts so far.
+    async fn record_failed_attempt(&self, id: Uuid) -> Result<i32, AppError>;
     async fn delete_user_tokens_by_type(&self, user_id: Uuid, token_type: TokenType) -> Result<(), AppError>;
+    async fn delete_user_tokens_by_type_except(&self, user_id: Uuid, token_type: TokenType, keep_id: Uuid) -> Result<(), AppError>;
+    /// Removes expired tokens. Consumed refresh tokens are kept while their family
+    /// has an unexpired token, so reuse can still be detected. Returns the number of removed tokens.
+    async fn delete_expired(&self) -> Result<u64, AppError>;
 }
 
 pub struct TokenRepositoryImpl {
@@ -33,17 +71,36 @@ impl TokenRepositoryImpl {
     }
 }
 
+pub(crate) fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
 #[async_trait]
 impl TokenRepository for TokenRepositoryImpl {
     async fn create(&self, token: &str, user_id: Uuid, expires_at: DateTime<Utc>, token_type: TokenType) -> Result<Token, AppError> {
         sqlx::query_as!(
             Token,
             r#"
-            INSERT INTO tokens (token, user_id, expires_at, token_type)
+            INSERT INTO tokens (token_hash, user_id, expires_at, token_type)
             VALUES ($1, $2, $3, $4)
-            RETURNING id, token, user_id, token_type AS "token_type!: TokenType", expires_at, blacklisted
+            RETURNING id, token_hash, user_id, token_type AS "token_type!: TokenType", expires_at, blacklisted, family_id, user_agent, ip_address, created_at, access_jti, access_expires_at
             "#,
-            token, user_id, expires_at, token_type as TokenType
+            hash_token(token), user_id, expires_at, token_type as TokenType
+        )
+        .fetch_one(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn create_in_family(&self, token: &str, user_id: Uuid, expires_at: DateTime<Utc>, family_id: Uuid, access: &AccessTokenRef, client: &ClientInfo) -> Result<Token, AppError> {
+        sqlx::query_as!(
+            Token,
+            r#"
+            INSERT INTO tokens (token_hash, user_id, expires_at, token_type, family_id, user_agent, ip_address, access_jti, access_expires_at)
+            VALUES ($1, $2, $3, 'refresh', $4, $5, $6, $7, $8)
+            RETURNING id, token_hash, user_id, token_type AS "token_type!: TokenType", expires_at, blacklisted, family_id, user_agent, ip_address, created_at, access_jti, access_expires_at
+            "#,
+            hash_token(token), user_id, expires_at, family_id, client.user_agent, client.ip_address, access.jti, access.expires_at
         )
         .fetch_one(&*self.db_pool)
         .await
@@ -54,16 +111,79 @@ impl TokenRepository for TokenRepositoryImpl {
         sqlx::query_as!(
             Token,
             r#"
-            SELECT id, token, user_id, token_type AS "token_type!: TokenType", expires_at, blacklisted
-            FROM tokens WHERE token = $1 AND blacklisted = false
+            SELECT id, token_hash, user_id, token_type AS "token_type!: TokenType", expires_at, blacklisted, family_id, user_agent, ip_address, created_at, access_jti, access_expires_at
+            FROM tokens WHERE token_hash = $1 AND blacklisted = false
             "#,
-            token
+            hash_token(token)
         )
         .fetch_optional(&*self.db_pool)
         .await
         .map_err(Into::into)
     }
 
+    async fn find_by_token_with_blacklisted(&self, token: &str) -> Result<Option<Token>, AppError> {
+        sqlx::query_as!(
+            Token,
+            r#"
+            SELECT id, token_hash, user_id, token_type AS "token_type!: TokenType", expires_at, blacklisted, family_id, user_agent, ip_address, created_at, access_jti, access_expires_at
+            FROM tokens WHERE token_hash = $1
+            "#,
+            hash_token(token)
+        )
+        .fetch_optional(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn blacklist(&self, id: Uuid) -> Result<bool, AppError> {
+        // Conditional update so two concurrent rotations can't both consume the same token
+        let result = sqlx::query("UPDATE tokens SET blacklisted = true WHERE id = $1 AND blacklisted = false")
+            .bind(id)
+            .execute(&*self.db_pool)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete_family(&self, family_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM tokens WHERE family_id = $1")
+            .bind(family_id)
+            .execute(&*self.db_pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn find_sessions_by_user(&self, user_id: Uuid) -> Result<Vec<Session>, AppError> {
+        sqlx::query_as!(
+            Session,
+            r#"
+            SELECT
+                t.family_id AS "id!",
+                t.user_agent,
+                t.ip_address,
+                (SELECT MIN(f.created_at) FROM tokens f WHERE f.family_id = t.family_id) AS "created_at!",
+                t.expires_at,
+                t.created_at AS last_used_at
+            FROM tokens t
+            WHERE t.user_id = $1 AND t.token_type = 'refresh' AND t.blacklisted = false
+                AND t.family_id IS NOT NULL AND t.expires_at > NOW()
+            ORDER BY t.created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn delete_user_family(&self, user_id: Uuid, family_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM tokens WHERE user_id = $1 AND family_id = $2")
+            .bind(user_id)
+            .bind(family_id)
+            .execute(&*self.db_pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     async fn delete(&self, id: Uuid) -> Result<(), AppError> {
         sqlx::query("DELETE FROM tokens WHERE id = $1")
             .bind(id)
@@ -72,6 +192,16 @@ impl TokenRepository for TokenRepositoryImpl {
         Ok(())
     }
 
+    async fn record_failed_attempt(&self, id: Uuid) -> Result<i32, AppError> {
+        let attempts: i32 = sqlx::query_scalar(
+            "UPDATE tokens SET failed_attempts = failed_attempts + 1 WHERE id = $1 RETURNING failed_attempts",
+        )
+        .bind(id)
+        .fetch_one(&*self.db_pool)
+        .await?;
+        Ok(attempts)
+    }
+
     async fn delete_user_tokens_by_type(&self, user_id: Uuid, token_type: TokenType) -> Result<(), AppError> {
         sqlx::query("DELETE FROM tokens WHERE user_id = $1 AND token_type = $2")
             .bind(user_id)
@@ -80,4 +210,29 @@ impl TokenRepository for TokenRepositoryImpl {
             .await?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    async fn delete_user_tokens_by_type_except(&self, user_id: Uuid, token_type: TokenType, keep_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM tokens WHERE user_id = $1 AND token_type = $2 AND id <> $3")
+            .bind(user_id)
+            .bind(token_type)
+            .bind(keep_id)
+            .execute(&*self.db_pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_expired(&self) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM tokens t
+            WHERE t.expires_at <= NOW()
+                AND (t.family_id IS NULL OR NOT EXISTS (
+                    SELECT 1 FROM tokens f WHERE f.family_id = t.family_id AND f.expires_at > NOW()
+                ))
+            "#,
+        )
+        .execute(&*self.db_pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,142 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::{error::AppError, repository::token_repository::hash_token};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct TwoFactor {
+    pub user_id: Uuid,
+    /// Base32 encoded TOTP secret.
+    pub secret: String,
+    pub enabled: bool,
+    pub last_used_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait TwoFactorRepository: Send + Sync {
+    async fn find_by_user(&self, user_id: Uuid) -> Result<Option<TwoFactor>, AppError>;
+    /// Starts (or restarts) an enrollment with a new, not yet enabled secret.
+    async fn upsert_secret(&self, user_id: Uuid, secret: &str) -> Result<TwoFactor, AppError>;
+    /// Enables 2FA and replaces the recovery codes, in one transaction.
+    async fn enable(&self, user_id: Uuid, step: i64, recovery_codes: &[String]) -> Result<(), AppError>;
+    /// Records an accepted code. Returns `false` if a code of the same or a later step was used already.
+    async fn use_step(&self, user_id: Uuid, step: i64) -> Result<bool, AppError>;
+    /// Marks a recovery code as used. Returns `false` if it is unknown or used already.
+    async fn use_recovery_code(&self, user_id: Uuid, code: &str) -> Result<bool, AppError>;
+    /// Removes the secret and every recovery code.
+    async fn delete(&self, user_id: Uuid) -> Result<(), AppError>;
+}
+
+pub struct TwoFactorRepositoryImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl TwoFactorRepositoryImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl TwoFactorRepository for TwoFactorRepositoryImpl {
+    async fn find_by_user(&self, user_id: Uuid) -> Result<Option<TwoFactor>, AppError> {
+        sqlx::query_as!(
+            TwoFactor,
+            r#"
+            SELECT user_id, secret, enabled, last_used_step, created_at, updated_at
+            FROM user_two_factor WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn upsert_secret(&self, user_id: Uuid, secret: &str) -> Result<TwoFactor, AppError> {
+        sqlx::query_as!(
+            TwoFactor,
+            r#"
+            INSERT INTO user_two_factor (user_id, secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+                SET secret = EXCLUDED.secret, enabled = false, last_used_step = NULL, updated_at = NOW()
+            RETURNING user_id, secret, enabled, last_used_step, created_at, updated_at
+            "#,
+            user_id, secret
+        )
+        .fetch_one(&*self.db_pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn enable(&self, user_id: Uuid, step: i64, recovery_codes: &[String]) -> Result<(), AppError> {
+        let mut tx = self.db_pool.begin().await?;
+
+        sqlx::query("UPDATE user_two_factor SET enabled = true, last_used_step = $2, updated_at = NOW() WHERE user_id = $1")
+            .bind(user_id)
+            .bind(step)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let code_hashes: Vec<String> = recovery_codes.iter().map(|code| hash_token(code)).collect();
+        sqlx::query("INSERT INTO recovery_codes (user_id, code_hash) SELECT $1, UNNEST($2::TEXT[])")
+            .bind(user_id)
+            .bind(&code_hashes)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn use_step(&self, user_id: Uuid, step: i64) -> Result<bool, AppError> {
+        // Conditional update so two concurrent requests can't both use the same code
+        let result = sqlx::query(
+            r#"
+            UPDATE user_two_factor SET last_used_step = $2, updated_at = NOW()
+            WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(&*self.db_pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn use_recovery_code(&self, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE recovery_codes SET used_at = NOW() WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(hash_token(code))
+        .execute(&*self.db_pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete(&self, user_id: Uuid) -> Result<(), AppError> {
+        let mut tx = self.db_pool.begin().await?;
+        sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM user_two_factor WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
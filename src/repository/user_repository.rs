@@ -1,19 +1,124 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use std::sync::Arc;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    domain::user_model::{Role, User},
-    error::AppError,
+    domain::user_model::User,
+    error::{AppError, ErrorCode},
 };
 
-#[derive(Debug, Deserialize)]
+/// Largest page the user listing returns, whatever `limit` asks for.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+#[derive(Debug, Default, Deserialize)]
 pub struct UserQueryOptions {
     pub page: Option<u32>,
     pub limit: Option<u32>,
+    pub role: Option<String>,
+    pub is_email_verified: Option<bool>,
+    /// Inclusive lower bound on `created_at`.
+    pub created_from: Option<DateTime<Utc>>,
+    /// Inclusive upper bound on `created_at`.
+    pub created_to: Option<DateTime<Utc>>,
+    /// Case-insensitive substring of the name or email.
+    pub search: Option<String>,
+    /// Newest first when not given.
+    pub sort_by: Option<UserSort>,
+}
+
+/// Columns the user listing can be sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserSortField {
+    Name,
+    Email,
+    Role,
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl UserSortField {
+    const ALL: [UserSortField; 5] = [
+        UserSortField::Name,
+        UserSortField::Email,
+        UserSortField::Role,
+        UserSortField::CreatedAt,
+        UserSortField::UpdatedAt,
+    ];
+
+    /// Name used in `sortBy`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserSortField::Name => "name",
+            UserSortField::Email => "email",
+            UserSortField::Role => "role",
+            UserSortField::CreatedAt => "createdAt",
+            UserSortField::UpdatedAt => "updatedAt",
+        }
+    }
+
+    fn column(&self) -> &'static str {
+        match self {
+            UserSortField::Name => "name",
+            UserSortField::Email => "email",
+            UserSortField::Role => "role",
+            UserSortField::CreatedAt => "created_at",
+            UserSortField::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    fn keyword(&self) -> &'static str {
+        match self {
+            SortDirection::Asc => " ASC",
+            SortDirection::Desc => " DESC",
+        }
+    }
+}
+
+/// Parsed `sortBy=field:asc|desc`; the direction defaults to `asc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct UserSort {
+    pub field: UserSortField,
+    pub direction: SortDirection,
+}
+
+impl Default for UserSort {
+    fn default() -> Self {
+        UserSort { field: UserSortField::CreatedAt, direction: SortDirection::Desc }
+    }
+}
+
+impl TryFrom<String> for UserSort {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let (field, direction) = value.split_once(':').unwrap_or((&value, "asc"));
+        let field = UserSortField::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == field)
+            .ok_or_else(|| {
+                let allowed: Vec<&str> = UserSortField::ALL.iter().map(|f| f.as_str()).collect();
+                format!("unknown sort field `{}`, expected one of {}", field, allowed.join(", "))
+            })?;
+        let direction = match direction.to_ascii_lowercase().as_str() {
+            "asc" => SortDirection::Asc,
+            "desc" => SortDirection::Desc,
+            other => return Err(format!("unknown sort direction `{}`, expected asc or desc", other)),
+        };
+        Ok(UserSort { field, direction })
+    }
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -32,9 +137,9 @@ pub struct PaginatedResult<T: for<'a> ToSchema<'a> + Serialize> {
 pub trait UserRepository: Send + Sync {
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, AppError>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, AppError>;
-    async fn create(&self, name: &str, email: &str, password_hash: &str, role: Role) -> Result<User, AppError>;
+    async fn create(&self, name: &str, email: &str, password_hash: &str, role: &str) -> Result<User, AppError>;
     async fn query_users(&self, options: UserQueryOptions) -> Result<PaginatedResult<User>, AppError>;
-    async fn update_by_id(&self, id: Uuid, name: Option<String>, email: Option<String>, password: Option<String>) -> Result<User, AppError>;
+    async fn update_by_id(&self, id: Uuid, name: Option<String>, email: Option<String>, password: Option<String>, role: Option<String>) -> Result<User, AppError>;
     async fn delete_by_id(&self, id: Uuid) -> Result<(), AppError>;
     async fn save(&self, user: &User) -> Result<User, AppError>;
 }
@@ -54,7 +159,7 @@ impl UserRepository for UserRepositoryImpl {
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, AppError> {
         sqlx::query_as!(
             User,
-            r#"SELECT id, name, email, password, role AS "role!: Role", is_email_verified, created_at, updated_at FROM users WHERE email = $1"#,
+            r#"SELECT id, name, email, password, role, role_permission_names(role) AS "permissions!", is_email_verified, locale, created_at, updated_at FROM users WHERE email = $1"#,
             email
         )
         .fetch_optional(&*self.db_pool)
@@ -65,7 +170,7 @@ impl UserRepository for UserRepositoryImpl {
     async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, AppError> {
         let user = sqlx::query_as!(
             User,
-             r#"SELECT id, name, email, password, role AS "role!: Role", is_email_verified, created_at, updated_at FROM users WHERE id = $1"#,
+             r#"SELECT id, name, email, password, role, role_permission_names(role) AS "permissions!", is_email_verified, locale, created_at, updated_at FROM users WHERE id = $1"#,
             id
         )
         .fetch_optional(&*self.db_pool)
@@ -73,15 +178,15 @@ impl UserRepository for UserRepositoryImpl {
         Ok(user)
     }
 
-    async fn create(&self, name: &str, email: &str, password_hash: &str, role: Role) -> Result<User, AppError> {
+    async fn create(&self, name: &str, email: &str, password_hash: &str, role: &str) -> Result<User, AppError> {
         let user = sqlx::query_as!(
             User,
             r#"
             INSERT INTO users (name, email, password, role)
             VALUES ($1, $2, $3, $4)
-            RETURNING id, name, email, password, role AS "role!: Role", is_email_verified, created_at, updated_at
+            RETURNING id, name, email, password, role, role_permission_names(role) AS "permissions!", is_email_verified, locale, created_at, updated_at
             "#,
-            name, email, password_hash, role as Role
+            name, email, password_hash, role
         )
         .fetch_one(&*self.db_pool)
         .await?;
@@ -89,24 +194,38 @@ impl UserRepository for UserRepositoryImpl {
     }
 
     async fn query_users(&self, options: UserQueryOptions) -> Result<PaginatedResult<User>, AppError> {
-        let page = options.page.unwrap_or(1);
-        let limit = options.limit.unwrap_or(10);
-        let offset = (page - 1) * limit;
+        let page = options.page.unwrap_or(1).max(1);
+        let limit = options.limit.unwrap_or(10).clamp(1, MAX_PAGE_SIZE);
+        let offset = i64::from(page - 1)
+            .checked_mul(i64::from(limit))
+            .ok_or_else(|| AppError::BadRequest(ErrorCode::InvalidQueryParameter.with("reason", "page is out of range")))?;
+        let sort = options.sort_by.unwrap_or_default();
 
-        let total_results: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
-            .fetch_one(&*self.db_pool).await?;
-        
-        let users = sqlx::query_as!(
-            User,
-            r#"
-            SELECT id, name, email, password, role AS "role!: Role", is_email_verified, created_at, updated_at
-            FROM users ORDER BY created_at DESC LIMIT $1 OFFSET $2
-            "#,
-            limit as i64,
-            offset as i64
-        )
-        .fetch_all(&*self.db_pool)
-        .await?;
+        let mut count_query = QueryBuilder::new("SELECT COUNT(*) FROM users");
+        push_filters(&mut count_query, &options);
+        let total_results: i64 = count_query
+            .build_query_scalar()
+            .fetch_one(&*self.db_pool)
+            .await?;
+
+        let mut users_query = QueryBuilder::new(
+            r#"SELECT id, name, email, password, role, role_permission_names(role) AS permissions, is_email_verified, locale, created_at, updated_at FROM users"#,
+        );
+        push_filters(&mut users_query, &options);
+        // Column and direction come from whitelists, never from the request text
+        users_query
+            .push(" ORDER BY ")
+            .push(sort.field.column())
+            .push(sort.direction.keyword())
+            .push(", id")
+            .push(" LIMIT ")
+            .push_bind(limit as i64)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let users = users_query
+            .build_query_as::<User>()
+            .fetch_all(&*self.db_pool)
+            .await?;
         
         let total_pages = if total_results > 0 {
             (total_results as f64 / limit as f64).ceil() as u32
@@ -123,11 +242,12 @@ impl UserRepository for UserRepositoryImpl {
         })
     }
 
-    async fn update_by_id(&self, id: Uuid, name: Option<String>, email: Option<String>, password: Option<String>) -> Result<User, AppError> {
-        let mut user = self.find_by_id(id).await?.ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    async fn update_by_id(&self, id: Uuid, name: Option<String>, email: Option<String>, password: Option<String>, role: Option<String>) -> Result<User, AppError> {
+        let mut user = self.find_by_id(id).await?.ok_or_else(|| AppError::NotFound(ErrorCode::UserNotFound.into()))?;
         if let Some(name) = name { user.name = name; }
         if let Some(email) = email { user.email = email; }
         if let Some(password) = password { user.password = password; }
+        if let Some(role) = role { user.role = role; }
         self.save(&user).await
     }
     
@@ -137,7 +257,7 @@ impl UserRepository for UserRepositoryImpl {
             .execute(&*self.db_pool)
             .await?;
         if result.rows_affected() == 0 {
-            return Err(AppError::NotFound("User not found".to_string()));
+            return Err(AppError::NotFound(ErrorCode::UserNotFound.into()));
         }
         Ok(())
     }
@@ -146,14 +266,98 @@ impl UserRepository for UserRepositoryImpl {
         sqlx::query_as!(
             User,
             r#"
-            UPDATE users SET name = $1, email = $2, password = $3, role = $4, is_email_verified = $5, updated_at = NOW()
-            WHERE id = $6
-            RETURNING id, name, email, password, role AS "role!: Role", is_email_verified, created_at, updated_at
+            UPDATE users SET name = $1, email = $2, password = $3, role = $4, is_email_verified = $5, locale = $6, updated_at = NOW()
+            WHERE id = $7
+            RETURNING id, name, email, password, role, role_permission_names(role) AS "permissions!", is_email_verified, locale, created_at, updated_at
             "#,
-            user.name, user.email, user.password, user.role.clone() as Role, user.is_email_verified, user.id
+            user.name, user.email, user.password, user.role, user.is_email_verified, user.locale, user.id
         )
         .fetch_one(&*self.db_pool)
         .await
         .map_err(Into::into)
     }
-}
\ No newline at end of file
+}
+
+/// Appends the `WHERE` clause shared by the listing and its count.
+fn push_filters(query: &mut QueryBuilder<'_, Postgres>, options: &UserQueryOptions) {
+    query.push(" WHERE TRUE");
+    if let Some(role) = &options.role {
+        query.push(" AND role = ").push_bind(role.clone());
+    }
+    if let Some(is_email_verified) = options.is_email_verified {
+        query.push(" AND is_email_verified = ").push_bind(is_email_verified);
+    }
+    if let Some(created_from) = options.created_from {
+        query.push(" AND created_at >= ").push_bind(created_from);
+    }
+    if let Some(created_to) = options.created_to {
+        query.push(" AND created_at <= ").push_bind(created_to);
+    }
+    if let Some(search) = options.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        let pattern = format!("%{}%", escape_like(search));
+        query
+            .push(" AND (name ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR email ILIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+}
+
+/// Makes `%`, `_` and `\` match literally in a `LIKE` pattern.
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Result<UserSort, String> {
+        UserSort::try_from(value.to_string())
+    }
+
+    #[test]
+    fn parses_every_field_to_its_column() {
+        for field in UserSortField::ALL {
+            let sort = parse(&format!("{}:desc", field.as_str())).unwrap();
+            assert_eq!(sort, UserSort { field, direction: SortDirection::Desc });
+        }
+        assert_eq!(UserSortField::CreatedAt.column(), "created_at");
+        assert_eq!(UserSortField::UpdatedAt.column(), "updated_at");
+    }
+
+    #[test]
+    fn direction_defaults_to_asc_and_ignores_case() {
+        assert_eq!(parse("name").unwrap().direction, SortDirection::Asc);
+        assert_eq!(parse("name:DESC").unwrap().direction, SortDirection::Desc);
+    }
+
+    #[test]
+    fn rejects_unknown_columns() {
+        for value in ["password", "created_at", "Name", "", "name; DROP TABLE users:asc"] {
+            let err = parse(value).unwrap_err();
+            assert!(err.starts_with("unknown sort field"), "{value}: {err}");
+        }
+        assert!(parse("password:asc").unwrap_err().ends_with("expected one of name, email, role, createdAt, updatedAt"));
+    }
+
+    #[test]
+    fn rejects_unknown_directions() {
+        assert_eq!(parse("name:up").unwrap_err(), "unknown sort direction `up`, expected asc or desc");
+        assert!(parse("name:").is_err());
+        assert!(parse("name:asc:desc").is_err());
+    }
+
+    #[test]
+    fn deserializes_through_the_whitelist() {
+        let sort: UserSort = serde_json::from_str("\"email:desc\"").unwrap();
+        assert_eq!(sort, UserSort { field: UserSortField::Email, direction: SortDirection::Desc });
+        assert!(serde_json::from_str::<UserSort>("\"1=1\"").is_err());
+    }
+
+    #[test]
+    fn defaults_to_newest_first() {
+        assert_eq!(UserSort::default(), UserSort { field: UserSortField::CreatedAt, direction: SortDirection::Desc });
+    }
+}
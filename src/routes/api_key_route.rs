@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use axum::{
+    routing::{delete, get, post},
+    middleware, Router,
+};
+use crate::{
+    domain::token_model::scopes,
+    handler::api_key_handler::*,
+    middleware::auth::{auth, require_scope, AuthState},
+    usecase::api_key_usecase::ApiKeyUsecase,
+};
+
+pub fn create_api_key_router(
+    api_key_usecase: Arc<dyn ApiKeyUsecase>,
+    auth_state: AuthState,
+) -> Router {
+    let profile_read_routes = Router::new()
+        .route("/auth/api-keys", get(get_my_api_keys_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::PROFILE_READ)));
+
+    let profile_write_routes = Router::new()
+        .route("/auth/api-keys", post(create_my_api_key_handler))
+        .route("/auth/api-keys/:id", delete(revoke_my_api_key_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::PROFILE_WRITE)));
+
+    // Access to another user's API keys is decided by `policy::ApiKeyResource` in the handlers
+    let users_read_routes = Router::new()
+        .route("/users/:id/api-keys", get(get_user_api_keys_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::USERS_READ)));
+
+    let users_write_routes = Router::new()
+        .route("/users/:id/api-keys", post(create_user_api_key_handler))
+        .route("/users/:id/api-keys/:key_id", delete(revoke_user_api_key_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::USERS_WRITE)));
+
+    Router::new()
+        .merge(profile_read_routes)
+        .merge(profile_write_routes)
+        .merge(users_read_routes)
+        .merge(users_write_routes)
+        .with_state(api_key_usecase)
+        .route_layer(middleware::from_fn_with_state(auth_state, auth))
+}
@@ -1,16 +1,42 @@
 use std::sync::Arc;
-use axum::{routing::post, Router};
+use axum::{middleware, routing::post, Router};
 
 use crate::{
-    handler::auth_handler::{login_handler, register_handler, logout_handler, refresh_tokens_handler},
+    domain::token_model::scopes,
+    handler::auth_handler::{
+        login_handler, register_handler, logout_handler, refresh_tokens_handler,
+        send_verification_email_handler, verify_email_handler,
+        forgot_password_handler, reset_password_handler, change_password_handler,
+        login_two_factor_handler, enroll_two_factor_handler, confirm_two_factor_handler,
+        disable_two_factor_handler,
+    },
+    middleware::auth::{auth, require_scope, AuthState},
     usecase::auth_usecase::AuthUsecase,
 };
 
-pub fn create_auth_router(auth_usecase: Arc<dyn AuthUsecase>) -> Router {
+pub fn create_auth_router(
+    auth_usecase: Arc<dyn AuthUsecase>,
+    auth_state: AuthState,
+) -> Router {
+    // Routes that require a logged in user
+    let protected_routes = Router::new()
+        .route("/send-verification-email", post(send_verification_email_handler))
+        .route("/change-password", post(change_password_handler))
+        .route("/2fa/enroll", post(enroll_two_factor_handler))
+        .route("/2fa/confirm", post(confirm_two_factor_handler))
+        .route("/2fa/disable", post(disable_two_factor_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::PROFILE_WRITE)))
+        .route_layer(middleware::from_fn_with_state(auth_state, auth));
+
     Router::new()
         .route("/register", post(register_handler))
         .route("/login", post(login_handler))
+        .route("/login/2fa", post(login_two_factor_handler))
         .route("/logout", post(logout_handler))
         .route("/refresh-tokens", post(refresh_tokens_handler))
+        .route("/verify-email", post(verify_email_handler))
+        .route("/forgot-password", post(forgot_password_handler))
+        .route("/reset-password", post(reset_password_handler))
+        .merge(protected_routes)
         .with_state(auth_usecase)
-}
\ No newline at end of file
+}
@@ -0,0 +1,10 @@
+use std::sync::Arc;
+use axum::{routing::get, Router};
+
+use crate::{handler::jwks_handler::get_jwks_handler, jwt::JwtKeys};
+
+pub fn create_jwks_router(jwt_keys: Arc<JwtKeys>) -> Router {
+    Router::new()
+        .route("/.well-known/jwks.json", get(get_jwks_handler))
+        .with_state(jwt_keys)
+}
@@ -1,25 +1,48 @@
 use std::sync::Arc;
-use axum::Router;
+use axum::{middleware, Router};
 use tower_http::cors::{Any, CorsLayer};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
+    config::CONFIG,
+    handler::fallback_handler::{method_not_allowed_handler, not_found_handler},
+    jwt::create_jwt_keys,
+    mailer::create_mailer,
+    password::{create_password_hasher, create_password_policy},
+    middleware::{auth::AuthState, locale::locale, request_id::request_id},
     repository::{
         user_repository::{UserRepository, UserRepositoryImpl},
         token_repository::{TokenRepository, TokenRepositoryImpl},
+        role_repository::{RoleRepository, RoleRepositoryImpl},
+        security_event_repository::{SecurityEventRepository, SecurityEventRepositoryImpl},
+        revoked_token_repository::{RevokedTokenRepository, RevokedTokenRepositoryImpl},
+        two_factor_repository::{TwoFactorRepository, TwoFactorRepositoryImpl},
+        api_key_repository::{ApiKeyRepository, ApiKeyRepositoryImpl},
+        login_throttle_repository::{LoginThrottleRepository, LoginThrottleRepositoryImpl},
     },
     routes::{
         auth_route::create_auth_router,
+        jwks_route::create_jwks_router,
         user_route::create_user_router,
+        role_route::create_role_router,
+        session_route::create_session_router,
+        api_key_route::create_api_key_router,
     },
     usecase::{
-        auth_usecase::{AuthUsecase, AuthUsecaseImpl},
+        auth_usecase::{AuthUsecase, AuthUsecaseImpl, LOGIN_FAILURE_MEMORY},
         user_usecase::{UserUsecase, UserUsecaseImpl},
+        role_usecase::{RoleUsecase, RoleUsecaseImpl},
+        session_usecase::{SessionUsecase, SessionUsecaseImpl},
+        api_key_usecase::{ApiKeyUsecase, ApiKeyUsecaseImpl},
     },
 };
 
+mod api_key_route;
 mod auth_route;
+mod jwks_route;
+mod role_route;
+mod session_route;
 mod swagger;
 mod user_route;
 
@@ -27,19 +50,79 @@ pub fn create_router(db_pool: Arc<sqlx::PgPool>) -> Router {
     // Repositories
     let user_repo: Arc<dyn UserRepository> = Arc::new(UserRepositoryImpl::new(db_pool.clone()));
     let token_repo: Arc<dyn TokenRepository> = Arc::new(TokenRepositoryImpl::new(db_pool.clone()));
+    let role_repo: Arc<dyn RoleRepository> = Arc::new(RoleRepositoryImpl::new(db_pool.clone()));
+    let security_event_repo: Arc<dyn SecurityEventRepository> = Arc::new(SecurityEventRepositoryImpl::new(db_pool.clone()));
+    let revoked_token_repo: Arc<dyn RevokedTokenRepository> = Arc::new(RevokedTokenRepositoryImpl::new(db_pool.clone()));
+    let two_factor_repo: Arc<dyn TwoFactorRepository> = Arc::new(TwoFactorRepositoryImpl::new(db_pool.clone()));
+    let api_key_repo: Arc<dyn ApiKeyRepository> = Arc::new(ApiKeyRepositoryImpl::new(db_pool.clone()));
+    let login_throttle_repo: Arc<dyn LoginThrottleRepository> = Arc::new(LoginThrottleRepositoryImpl::new(db_pool.clone()));
+
+    spawn_pruner(token_repo.clone(), revoked_token_repo.clone(), login_throttle_repo.clone());
+
+    // Mailer
+    let mailer = create_mailer().expect("Failed to create mailer");
+
+    // Password hashing
+    let password_hasher = create_password_hasher().expect("Failed to create password hasher");
+    let password_policy = create_password_policy().expect("Failed to load password policy");
+
+    // JWT signing and verification keys
+    let jwt_keys = create_jwt_keys().expect("Failed to load JWT keys");
     
     // Usecases
-    let auth_usecase: Arc<dyn AuthUsecase> = Arc::new(AuthUsecaseImpl::new(user_repo.clone(), token_repo.clone()));
-    let user_usecase: Arc<dyn UserUsecase> = Arc::new(UserUsecaseImpl::new(user_repo.clone()));
+    let auth_usecase: Arc<dyn AuthUsecase> = Arc::new(AuthUsecaseImpl::new(user_repo.clone(), token_repo.clone(), revoked_token_repo.clone(), security_event_repo.clone(), two_factor_repo.clone(), login_throttle_repo.clone(), password_hasher.clone(), password_policy.clone(), jwt_keys.clone(), mailer.clone()));
+    let user_usecase: Arc<dyn UserUsecase> = Arc::new(UserUsecaseImpl::new(user_repo.clone(), role_repo.clone(), token_repo.clone(), revoked_token_repo.clone(), login_throttle_repo.clone(), security_event_repo.clone(), password_hasher.clone(), password_policy.clone()));
+    let role_usecase: Arc<dyn RoleUsecase> = Arc::new(RoleUsecaseImpl::new(role_repo.clone()));
+    let session_usecase: Arc<dyn SessionUsecase> = Arc::new(SessionUsecaseImpl::new(user_repo.clone(), token_repo.clone(), revoked_token_repo.clone()));
+    let api_key_usecase: Arc<dyn ApiKeyUsecase> = Arc::new(ApiKeyUsecaseImpl::new(user_repo.clone(), api_key_repo.clone(), security_event_repo.clone()));
+
+    let auth_state = AuthState { user_repo: user_repo.clone(), revoked_token_repo: revoked_token_repo.clone(), api_key_repo, jwt_keys: jwt_keys.clone() };
 
     let cors = CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any);
     
     let v1_routes = Router::new()
-        .nest("/auth", create_auth_router(auth_usecase.clone()))
-        .nest("/users", create_user_router(user_usecase.clone(), user_repo.clone()));
+        .nest("/auth", create_auth_router(auth_usecase.clone(), auth_state.clone()))
+        .nest("/users", create_user_router(user_usecase.clone(), auth_state.clone()))
+        .merge(create_role_router(role_usecase.clone(), auth_state.clone()))
+        .merge(create_session_router(session_usecase.clone(), auth_state.clone()))
+        .merge(create_api_key_router(api_key_usecase.clone(), auth_state));
     
     Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", swagger::ApiDoc::openapi()))
+        .merge(create_jwks_router(jwt_keys))
         .nest("/v1", v1_routes)
+        .fallback(not_found_handler)
+        .method_not_allowed_fallback(method_not_allowed_handler)
+        .layer(middleware::from_fn(locale))
+        .layer(middleware::from_fn(request_id))
         .layer(cors)
-}
\ No newline at end of file
+}
+
+/// Periodically removes denylist entries whose access token has expired, expired
+/// tokens, and failed login counters that are no longer relevant.
+fn spawn_pruner(token_repo: Arc<dyn TokenRepository>, revoked_token_repo: Arc<dyn RevokedTokenRepository>, login_throttle_repo: Arc<dyn LoginThrottleRepository>) {
+    let period = humantime::parse_duration(&CONFIG.revoked_token_prune_interval)
+        .expect("Invalid REVOKED_TOKEN_PRUNE_INTERVAL");
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match revoked_token_repo.delete_expired().await {
+                Ok(0) => {}
+                Ok(count) => tracing::debug!("Pruned {} expired revoked access tokens", count),
+                Err(err) => tracing::error!("Failed to prune revoked access tokens: {:?}", err),
+            }
+            match token_repo.delete_expired().await {
+                Ok(0) => {}
+                Ok(count) => tracing::debug!("Pruned {} expired tokens", count),
+                Err(err) => tracing::error!("Failed to prune expired tokens: {:?}", err),
+            }
+            match login_throttle_repo.delete_stale(LOGIN_FAILURE_MEMORY).await {
+                Ok(0) => {}
+                Ok(count) => tracing::debug!("Pruned {} stale login throttles", count),
+                Err(err) => tracing::error!("Failed to prune login throttles: {:?}", err),
+            }
+        }
+    });
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+use axum::{
+    routing::{get, patch, post},
+    middleware, Router,
+};
+use crate::{
+    domain::{role_model::permissions, token_model::scopes},
+    handler::role_handler::*,
+    middleware::auth::{auth, require_permission, require_scope, AuthState},
+    usecase::role_usecase::RoleUsecase,
+};
+
+pub fn create_role_router(
+    role_usecase: Arc<dyn RoleUsecase>,
+    auth_state: AuthState,
+) -> Router {
+    let read_routes = Router::new()
+        .route("/roles", get(get_roles_handler))
+        .route("/roles/:id", get(get_role_handler))
+        .route("/permissions", get(get_permissions_handler))
+        .route_layer(middleware::from_fn(require_permission(permissions::ROLES_READ)))
+        .route_layer(middleware::from_fn(require_scope(scopes::ROLES_READ)));
+
+    let write_routes = Router::new()
+        .route("/roles", post(create_role_handler))
+        .route(
+            "/roles/:id",
+            patch(update_role_handler).delete(delete_role_handler),
+        )
+        .route_layer(middleware::from_fn(require_permission(permissions::ROLES_WRITE)))
+        .route_layer(middleware::from_fn(require_scope(scopes::ROLES_WRITE)));
+
+    Router::new()
+        .merge(read_routes)
+        .merge(write_routes)
+        .with_state(role_usecase)
+        .route_layer(middleware::from_fn_with_state(auth_state, auth))
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use axum::{
+    routing::{delete, get},
+    middleware, Router,
+};
+use crate::{
+    domain::token_model::scopes,
+    handler::session_handler::*,
+    middleware::auth::{auth, require_scope, AuthState},
+    usecase::session_usecase::SessionUsecase,
+};
+
+pub fn create_session_router(
+    session_usecase: Arc<dyn SessionUsecase>,
+    auth_state: AuthState,
+) -> Router {
+    let profile_read_routes = Router::new()
+        .route("/auth/sessions", get(get_my_sessions_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::PROFILE_READ)));
+
+    let profile_write_routes = Router::new()
+        .route("/auth/sessions", delete(revoke_my_sessions_handler))
+        .route("/auth/sessions/:id", delete(revoke_my_session_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::PROFILE_WRITE)));
+
+    // Access to another user's sessions is decided by `policy::SessionResource` in the handlers
+    let users_read_routes = Router::new()
+        .route("/users/:id/sessions", get(get_user_sessions_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::USERS_READ)));
+
+    let users_write_routes = Router::new()
+        .route("/users/:id/sessions", delete(revoke_user_sessions_handler))
+        .route("/users/:id/sessions/:session_id", delete(revoke_user_session_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::USERS_WRITE)));
+
+    Router::new()
+        .merge(profile_read_routes)
+        .merge(profile_write_routes)
+        .merge(users_read_routes)
+        .merge(users_write_routes)
+        .with_state(session_usecase)
+        .route_layer(middleware::from_fn_with_state(auth_state, auth))
+}
@@ -4,13 +4,25 @@ use utoipa::{
 };
 use crate::{
     domain::{
+        api_key_model::{ApiKey, CreatedApiKey},
+        role_model::{Permission, Role},
+        session_model::Session,
         token_model::{TokenDetails, TokenResponse},
+        two_factor_model::{MfaChallenge, RecoveryCodes, TwoFactorEnrollment},
         user_model::FilteredUser,
     },
-    error::ErrorResponse,
+    error::{ErrorCode, FieldError, ProblemDetails},
     handler::{
-        auth_handler::{self, AuthResponse, LoginSchema, RefreshTokenSchema, RegisterSchema},
-        user_handler::{self, CreateUserSchema, UpdateUserSchema},
+        api_key_handler::{self, CreateApiKeySchema},
+        auth_handler::{
+            self, AuthResponse, ChangePasswordSchema, ForgotPasswordSchema, LoginSchema,
+            RefreshTokenSchema, RegisterSchema, ResetPasswordSchema,
+            LoginTwoFactorSchema, TwoFactorCodeSchema,
+        },
+        role_handler::{self, CreateRoleSchema, UpdateRoleSchema},
+        session_handler,
+        jwks_handler,
+        user_handler::{self, CreateUserSchema, UpdateProfileSchema, UpdateUserSchema},
     },
     repository::user_repository::PaginatedResult,
 };
@@ -33,28 +45,70 @@ impl Modify for SecurityAddon {
     paths(
         auth_handler::register_handler,
         auth_handler::login_handler,
+        auth_handler::login_two_factor_handler,
         auth_handler::logout_handler,
         auth_handler::refresh_tokens_handler,
+        auth_handler::send_verification_email_handler,
+        auth_handler::verify_email_handler,
+        auth_handler::forgot_password_handler,
+        auth_handler::reset_password_handler,
+        auth_handler::change_password_handler,
+        auth_handler::enroll_two_factor_handler,
+        auth_handler::confirm_two_factor_handler,
+        auth_handler::disable_two_factor_handler,
         user_handler::create_user_handler,
         user_handler::get_users_handler,
         user_handler::get_user_handler,
         user_handler::update_user_handler,
         user_handler::delete_user_handler,
+        user_handler::unlock_user_handler,
+        user_handler::get_me_handler,
+        user_handler::update_me_handler,
+        user_handler::delete_me_handler,
+        role_handler::create_role_handler,
+        role_handler::get_roles_handler,
+        role_handler::get_role_handler,
+        role_handler::update_role_handler,
+        role_handler::delete_role_handler,
+        role_handler::get_permissions_handler,
+        session_handler::get_my_sessions_handler,
+        session_handler::revoke_my_session_handler,
+        session_handler::revoke_my_sessions_handler,
+        session_handler::get_user_sessions_handler,
+        session_handler::revoke_user_session_handler,
+        session_handler::revoke_user_sessions_handler,
+        api_key_handler::create_my_api_key_handler,
+        api_key_handler::get_my_api_keys_handler,
+        api_key_handler::revoke_my_api_key_handler,
+        api_key_handler::create_user_api_key_handler,
+        api_key_handler::get_user_api_keys_handler,
+        api_key_handler::revoke_user_api_key_handler,
+        jwks_handler::get_jwks_handler,
     ),
     components(
         schemas(
             // Schemas
             AuthResponse, LoginSchema, RefreshTokenSchema, RegisterSchema,
-            CreateUserSchema, UpdateUserSchema,
+            ForgotPasswordSchema, ResetPasswordSchema, ChangePasswordSchema,
+            LoginTwoFactorSchema, TwoFactorCodeSchema, MfaChallenge, TwoFactorEnrollment, RecoveryCodes,
+            CreateUserSchema, UpdateUserSchema, UpdateProfileSchema,
             FilteredUser,
+            Role, Permission, CreateRoleSchema, UpdateRoleSchema,
+            Session,
+            ApiKey, CreatedApiKey, CreateApiKeySchema,
             TokenResponse, TokenDetails,
-            ErrorResponse,
+            ProblemDetails,
+            ErrorCode,
+            FieldError,
             PaginatedResult<FilteredUser>,
         )
     ),
     tags(
         (name = "Auth", description = "Authentication endpoints"),
-        (name = "Users", description = "User management endpoints")
+        (name = "Users", description = "User management endpoints"),
+        (name = "Roles", description = "Role and permission management endpoints"),
+        (name = "Sessions", description = "Session listing and revocation endpoints"),
+        (name = "API Keys", description = "Personal access tokens for machine clients")
     ),
     modifiers(&SecurityAddon)
 )]
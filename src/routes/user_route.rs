@@ -1,34 +1,54 @@
 use std::sync::Arc;
 use axum::{
-    routing::{delete, get, patch, post},
+    routing::{get, patch, post},
     middleware, Router,
 };
 use crate::{
+    domain::{role_model::permissions, token_model::scopes},
     handler::user_handler::*,
-    middleware::auth::{admin_only, auth},
-    repository::user_repository::UserRepository,
+    middleware::auth::{auth, require_permission, require_scope, AuthState},
     usecase::user_usecase::UserUsecase,
 };
 
 pub fn create_user_router(
     user_usecase: Arc<dyn UserUsecase>,
-    user_repo: Arc<dyn UserRepository>,
+    auth_state: AuthState,
 ) -> Router {
-    // Routes that can only be accessed by Admin
-    let admin_routes = Router::new()
-        .route("/", post(create_user_handler).get(get_users_handler))
-        .route(
-            "/:id",
-            patch(update_user_handler).delete(delete_user_handler),
-        )
-        .route_layer(middleware::from_fn(admin_only));
+    // Routes that require a permission on every user
+    let list_routes = Router::new()
+        .route("/", get(get_users_handler))
+        .route_layer(middleware::from_fn(require_permission(permissions::USERS_READ)));
 
-    // Routes that are accessible to all authenticated users
-    let public_routes = Router::new().route("/:id", get(get_user_handler));
+    let create_routes = Router::new()
+        .route("/", post(create_user_handler))
+        .route("/:id/unlock", post(unlock_user_handler))
+        .route_layer(middleware::from_fn(require_permission(permissions::USERS_WRITE)));
+
+    // Access to a specific user is decided by `policy::UserResource` in the handlers.
+    let read_routes = Router::new()
+        .merge(list_routes)
+        .route("/:id", get(get_user_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::USERS_READ)));
+
+    let write_routes = Router::new()
+        .merge(create_routes)
+        .route("/:id", patch(update_user_handler).delete(delete_user_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::USERS_WRITE)));
+
+    // The own account, accessible to all authenticated users
+    let profile_read_routes = Router::new()
+        .route("/me", get(get_me_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::PROFILE_READ)));
+
+    let profile_write_routes = Router::new()
+        .route("/me", patch(update_me_handler).delete(delete_me_handler))
+        .route_layer(middleware::from_fn(require_scope(scopes::PROFILE_WRITE)));
 
     Router::new()
-        .merge(admin_routes)
-        .merge(public_routes)
+        .merge(read_routes)
+        .merge(write_routes)
+        .merge(profile_read_routes)
+        .merge(profile_write_routes)
         .with_state(user_usecase)
-        .route_layer(middleware::from_fn_with_state(user_repo, auth))
-}
\ No newline at end of file
+        .route_layer(middleware::from_fn_with_state(auth_state, auth))
+}
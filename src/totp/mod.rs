@@ -0,0 +1,85 @@
+use rand::{distributions::Slice, Rng};
+use std::time::{SystemTime, UNIX_EPOCH};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::{config::CONFIG, error::AppError};
+
+/// RFC 6238 defaults understood by every authenticator app.
+const DIGITS: usize = 6;
+const STEP_SECONDS: u64 = 30;
+/// Codes of the previous and the next time step are accepted as well, to allow for clock drift.
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+/// Lowercase letters and digits without look-alikes such as `0`/`o` and `1`/`l`.
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// Returns a new random base32 encoded secret.
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+/// The `otpauth://` URI that authenticator apps import, usually as a QR code.
+pub fn otpauth_uri(secret: &str, account_name: &str) -> Result<String, AppError> {
+    Ok(build(secret, account_name)?.get_url())
+}
+
+/// Checks `code` against the current time step and its neighbours.
+///
+/// Returns the matching time step, which must be newer than `last_used_step`
+/// so an accepted code can't be used a second time.
+pub fn verify(secret: &str, code: &str, last_used_step: Option<i64>) -> Result<Option<i64>, AppError> {
+    let totp = build(secret, "")?;
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| AppError::InternalServerError)?
+        .as_secs() as i64;
+    let current_step = now / STEP_SECONDS as i64;
+
+    let step = (current_step - ALLOWED_DRIFT_STEPS..=current_step + ALLOWED_DRIFT_STEPS)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| totp.check(&code, *step as u64 * STEP_SECONDS));
+    Ok(step)
+}
+
+/// Generates one-time recovery codes formatted as `xxxxx-xxxxx`.
+pub fn generate_recovery_codes() -> Vec<String> {
+    let alphabet = Slice::new(RECOVERY_CODE_ALPHABET).expect("alphabet is not empty");
+    let mut rng = rand::thread_rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let code: String = (&mut rng).sample_iter(&alphabet).take(10).map(|&c| c as char).collect();
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// Recovery codes are compared without the dash and case-insensitively.
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn build(secret: &str, account_name: &str) -> Result<TOTP, AppError> {
+    let secret = Secret::Encoded(secret.to_string()).to_bytes().map_err(|err| {
+        tracing::error!("Invalid TOTP secret: {:?}", err);
+        AppError::InternalServerError
+    })?;
+    // Drift is handled by `verify`, so the library itself only checks the exact step
+    TOTP::new(
+        Algorithm::SHA1,
+        DIGITS,
+        0,
+        STEP_SECONDS,
+        secret,
+        Some(CONFIG.totp_issuer.clone()),
+        account_name.to_string(),
+    )
+    .map_err(|err| {
+        tracing::error!("Failed to build TOTP: {:?}", err);
+        AppError::InternalServerError
+    })
+}
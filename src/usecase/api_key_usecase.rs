@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        api_key_model::{ApiKey, CreatedApiKey, API_KEY_PREFIX},
+        token_model::scopes,
+    },
+    error::{AppError, ErrorCode},
+    repository::{
+        api_key_repository::ApiKeyRepository,
+        security_event_repository::{event_types, SecurityEventRepository},
+        user_repository::UserRepository,
+    },
+};
+
+/// Random characters after `pat_`, about 238 bits of entropy.
+const API_KEY_RANDOM_LENGTH: usize = 40;
+/// Random characters of the key that are kept in clear as its prefix.
+const API_KEY_VISIBLE_LENGTH: usize = 8;
+
+#[async_trait]
+pub trait ApiKeyUsecase: Send + Sync {
+    /// Creates a key for `user_id`. `granted_scopes` are the scopes of the
+    /// credentials used for the request; a key can't get more than those.
+    async fn create_api_key(&self, user_id: Uuid, name: &str, scopes: Vec<String>, expires_in_days: Option<i64>, granted_scopes: &[String]) -> Result<CreatedApiKey, AppError>;
+    async fn get_api_keys(&self, user_id: Uuid) -> Result<Vec<ApiKey>, AppError>;
+    async fn revoke_api_key(&self, user_id: Uuid, id: Uuid) -> Result<(), AppError>;
+}
+
+pub struct ApiKeyUsecaseImpl {
+    user_repo: Arc<dyn UserRepository>,
+    api_key_repo: Arc<dyn ApiKeyRepository>,
+    security_event_repo: Arc<dyn SecurityEventRepository>,
+}
+
+impl ApiKeyUsecaseImpl {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        api_key_repo: Arc<dyn ApiKeyRepository>,
+        security_event_repo: Arc<dyn SecurityEventRepository>,
+    ) -> Self {
+        Self { user_repo, api_key_repo, security_event_repo }
+    }
+
+    async fn ensure_user_exists(&self, user_id: Uuid) -> Result<(), AppError> {
+        self.user_repo.find_by_id(user_id).await?
+            .ok_or_else(|| AppError::NotFound(ErrorCode::UserNotFound.into()))?;
+        Ok(())
+    }
+}
+
+fn generate_api_key() -> String {
+    let random: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(API_KEY_RANDOM_LENGTH)
+        .map(char::from)
+        .collect();
+    format!("{}{}", API_KEY_PREFIX, random)
+}
+
+fn check_scopes(requested: &[String], granted: &[String]) -> Result<(), AppError> {
+    if requested.is_empty() {
+        return Err(AppError::BadRequest(ErrorCode::ScopesRequired.into()));
+    }
+    for scope in requested {
+        if !scopes::ALL.contains(&scope.as_str()) {
+            return Err(AppError::BadRequest(ErrorCode::UnknownScope.with("scope", scope)));
+        }
+        if !granted.contains(scope) {
+            return Err(AppError::Forbidden(ErrorCode::ScopeMissing.with("scope", scope)));
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ApiKeyUsecase for ApiKeyUsecaseImpl {
+    async fn create_api_key(&self, user_id: Uuid, name: &str, mut scopes: Vec<String>, expires_in_days: Option<i64>, granted_scopes: &[String]) -> Result<CreatedApiKey, AppError> {
+        self.ensure_user_exists(user_id).await?;
+        check_scopes(&scopes, granted_scopes)?;
+        scopes.sort();
+        scopes.dedup();
+
+        let key = generate_api_key();
+        let prefix = &key[..API_KEY_PREFIX.len() + API_KEY_VISIBLE_LENGTH];
+        let expires_at = expires_in_days.map(|days| Utc::now() + Duration::days(days));
+
+        let api_key = self.api_key_repo.create(user_id, name, prefix, &key, &scopes, expires_at).await?;
+
+        self.security_event_repo
+            .create(Some(user_id), event_types::API_KEY_CREATED, &format!("API key '{}' ({}) created", api_key.name, api_key.prefix))
+            .await?;
+
+        Ok(CreatedApiKey { key, api_key })
+    }
+
+    async fn get_api_keys(&self, user_id: Uuid) -> Result<Vec<ApiKey>, AppError> {
+        self.ensure_user_exists(user_id).await?;
+        self.api_key_repo.find_by_user(user_id).await
+    }
+
+    async fn revoke_api_key(&self, user_id: Uuid, id: Uuid) -> Result<(), AppError> {
+        if !self.api_key_repo.delete_user_key(user_id, id).await? {
+            return Err(AppError::NotFound(ErrorCode::ApiKeyNotFound.into()));
+        }
+
+        self.security_event_repo
+            .create(Some(user_id), event_types::API_KEY_REVOKED, &format!("API key {} revoked", id))
+            .await?;
+
+        Ok(())
+    }
+}
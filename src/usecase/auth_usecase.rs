@@ -1,63 +1,247 @@
 use async_trait::async_trait;
-use bcrypt::{hash, verify, DEFAULT_COST};
-use chrono::{Duration, Utc};
-use jsonwebtoken::{encode, decode, EncodingKey, DecodingKey, Validation};
+use chrono::{DateTime, Duration, Utc};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
     config::CONFIG,
     domain::{
-        token_model::{TokenClaims, TokenDetails, TokenResponse, TokenType},
-        user_model::{FilteredUser, Role, User},
+        token_model::{scopes, TokenClaims, TokenDetails, TokenResponse, TokenType},
+        role_model::DEFAULT_ROLE,
+        session_model::ClientInfo,
+        two_factor_model::{LoginOutcome, MfaChallenge, RecoveryCodes, TwoFactorEnrollment},
+        user_model::{FilteredUser, User},
     },
-    error::AppError,
-    handler::auth_handler::{LoginSchema, RegisterSchema},
+    error::{AppError, ErrorCode},
+    i18n::Locale,
+    handler::auth_handler::{ChangePasswordSchema, LoginSchema, LoginTwoFactorSchema, RegisterSchema},
+    jwt::JwtKeys,
+    mailer::{templates, Mailer},
+    password::{PasswordHasher, PasswordPolicy},
     repository::{
-        token_repository::{Token, TokenRepository},
+        revoked_token_repository::RevokedTokenRepository,
+        security_event_repository::{event_types, SecurityEventRepository},
+        token_repository::{AccessTokenRef, Token, TokenRepository},
+        two_factor_repository::{TwoFactor, TwoFactorRepository},
+        login_throttle_repository::{LoginThrottleKey, LoginThrottleRepository},
         user_repository::UserRepository,
     },
+    totp,
 };
 
-fn create_token(user_id: Uuid, secret: &str, expires_in_str: &str, token_type: &str) -> Result<TokenDetails, AppError> {
+/// Wrong codes accepted for one mfaPending token before the login has to start over.
+const MAX_MFA_ATTEMPTS: i32 = 5;
+
+/// Failed logins and lockouts are forgotten after a day without failures.
+pub const LOGIN_FAILURE_MEMORY: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+fn create_token(keys: &JwtKeys, user_id: Uuid, jti: Uuid, expires_in_str: &str, token_type: &str, scope: &str) -> Result<TokenDetails, AppError> {
     let expires_in = humantime::parse_duration(expires_in_str).map_err(|_| AppError::InternalServerError)?;
     let now = Utc::now();
     let expires_at = now + Duration::from_std(expires_in).map_err(|_| AppError::InternalServerError)?;
     
     let claims = TokenClaims {
         sub: user_id,
+        iss: CONFIG.jwt_issuer.clone(),
+        aud: CONFIG.jwt_audience.clone(),
+        jti,
         iat: now.timestamp() as usize,
         exp: expires_at.timestamp() as usize,
         token_type: token_type.to_string(),
+        scope: scope.to_string(),
     };
 
-    let token = encode(&jsonwebtoken::Header::default(), &claims, &EncodingKey::from_secret(secret.as_ref()))?;
+    let token = keys.encode(&claims)?;
     Ok(TokenDetails { token, expires_in: expires_at.timestamp() })
 }
 
-fn create_auth_tokens(user_id: Uuid) -> Result<TokenResponse, AppError> {
-    let access_token = create_token(user_id, &CONFIG.jwt_secret, &CONFIG.jwt_access_token_expires_in, "access")?;
-    let refresh_token = create_token(user_id, &CONFIG.jwt_secret, &CONFIG.jwt_refresh_token_expires_in, "refresh")?;
+fn create_auth_tokens(keys: &JwtKeys, user_id: Uuid, access_jti: Uuid) -> Result<TokenResponse, AppError> {
+    let access_token = create_token(keys, user_id, access_jti, &CONFIG.jwt_access_token_expires_in, "access", &scopes::ALL.join(" "))?;
+    let refresh_token = create_token(keys, user_id, Uuid::new_v4(), &CONFIG.jwt_refresh_token_expires_in, "refresh", "")?;
     Ok(TokenResponse { access_token, refresh_token })
 }
 
+fn token_expires_at(token: &TokenDetails) -> Result<DateTime<Utc>, AppError> {
+    DateTime::from_timestamp(token.expires_in, 0).ok_or(AppError::InternalServerError)
+}
+
+/// Duration of the next lockout after `lockouts` lockouts in a row, per `LOGIN_*LOCKOUT_DURATION`.
+fn next_lockout_duration(lockouts: i32) -> Result<Duration, AppError> {
+    let base = humantime::parse_duration(&CONFIG.login_lockout_duration).map_err(|_| AppError::InternalServerError)?;
+    let max = humantime::parse_duration(&CONFIG.login_max_lockout_duration).map_err(|_| AppError::InternalServerError)?;
+    Duration::from_std(lockout_duration(base, max, lockouts)).map_err(|_| AppError::InternalServerError)
+}
+
+/// `base`, doubled for each of the previous `lockouts`, up to `max`.
+fn lockout_duration(base: std::time::Duration, max: std::time::Duration, lockouts: i32) -> std::time::Duration {
+    base.saturating_mul(2u32.saturating_pow(lockouts.max(0) as u32)).min(max)
+}
+
+fn login_locked(locked_until: DateTime<Utc>) -> AppError {
+    let remaining_ms = (locked_until - Utc::now()).num_milliseconds().max(0) as u64;
+    AppError::TooManyRequests {
+        code: ErrorCode::LoginLocked,
+        retry_after: remaining_ms.div_ceil(1000).max(1),
+    }
+}
+
 
 #[async_trait]
 pub trait AuthUsecase: Send + Sync {
-    async fn register(&self, data: RegisterSchema) -> Result<(FilteredUser, TokenResponse), AppError>;
-    async fn login(&self, data: LoginSchema) -> Result<(FilteredUser, TokenResponse), AppError>;
+    async fn register(&self, data: RegisterSchema, client: ClientInfo) -> Result<(FilteredUser, TokenResponse), AppError>;
+    /// Returns an `MfaChallenge` instead of tokens when the user has 2FA enabled.
+    async fn login(&self, data: LoginSchema, client: ClientInfo) -> Result<LoginOutcome, AppError>;
+    async fn login_two_factor(&self, data: LoginTwoFactorSchema, client: ClientInfo) -> Result<(FilteredUser, TokenResponse), AppError>;
     async fn logout(&self, refresh_token: String) -> Result<(), AppError>;
-    async fn refresh_auth(&self, refresh_token: String) -> Result<TokenResponse, AppError>;
+    async fn refresh_auth(&self, refresh_token: String, client: ClientInfo) -> Result<TokenResponse, AppError>;
+    /// `locale` is the request's, already overridden by the user's preference.
+    async fn send_verification_email(&self, user: User, locale: Locale) -> Result<(), AppError>;
+    async fn verify_email(&self, token: String) -> Result<(), AppError>;
+    /// The email uses the account's preferred language, else `locale`.
+    async fn forgot_password(&self, email: String, locale: Locale) -> Result<(), AppError>;
+    async fn reset_password(&self, token: String, new_password: String) -> Result<(), AppError>;
+    async fn change_password(&self, user: User, data: ChangePasswordSchema) -> Result<(), AppError>;
+    async fn enroll_two_factor(&self, user: User) -> Result<TwoFactorEnrollment, AppError>;
+    async fn confirm_two_factor(&self, user: User, code: String) -> Result<RecoveryCodes, AppError>;
+    async fn disable_two_factor(&self, user: User, code: String) -> Result<(), AppError>;
 }
 
 pub struct AuthUsecaseImpl {
     user_repo: Arc<dyn UserRepository>,
     token_repo: Arc<dyn TokenRepository>,
+    revoked_token_repo: Arc<dyn RevokedTokenRepository>,
+    security_event_repo: Arc<dyn SecurityEventRepository>,
+    two_factor_repo: Arc<dyn TwoFactorRepository>,
+    login_throttle_repo: Arc<dyn LoginThrottleRepository>,
+    password_hasher: Arc<dyn PasswordHasher>,
+    password_policy: Arc<PasswordPolicy>,
+    jwt_keys: Arc<JwtKeys>,
+    mailer: Arc<dyn Mailer>,
 }
 
 impl AuthUsecaseImpl {
-    pub fn new(user_repo: Arc<dyn UserRepository>, token_repo: Arc<dyn TokenRepository>) -> Self {
-        Self { user_repo, token_repo }
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        revoked_token_repo: Arc<dyn RevokedTokenRepository>,
+        security_event_repo: Arc<dyn SecurityEventRepository>,
+        two_factor_repo: Arc<dyn TwoFactorRepository>,
+        login_throttle_repo: Arc<dyn LoginThrottleRepository>,
+        password_hasher: Arc<dyn PasswordHasher>,
+        password_policy: Arc<PasswordPolicy>,
+        jwt_keys: Arc<JwtKeys>,
+        mailer: Arc<dyn Mailer>,
+    ) -> Self {
+        Self { user_repo, token_repo, revoked_token_repo, security_event_repo, two_factor_repo, login_throttle_repo, password_hasher, password_policy, jwt_keys, mailer }
+    }
+
+    /// Accepts a current TOTP code or an unused recovery code. Both are single-use.
+    async fn verify_second_factor(&self, two_factor: &TwoFactor, code: &str) -> Result<bool, AppError> {
+        if let Some(step) = totp::verify(&two_factor.secret, code, two_factor.last_used_step)? {
+            return self.two_factor_repo.use_step(two_factor.user_id, step).await;
+        }
+
+        let recovery_code = totp::normalize_recovery_code(code);
+        if recovery_code.is_empty() || !self.two_factor_repo.use_recovery_code(two_factor.user_id, &recovery_code).await? {
+            return Ok(false);
+        }
+        self.security_event_repo
+            .create(Some(two_factor.user_id), event_types::RECOVERY_CODE_USED, "A recovery code was used")
+            .await?;
+        Ok(true)
+    }
+
+    async fn ensure_login_allowed(&self, key: &LoginThrottleKey<'_>) -> Result<(), AppError> {
+        match self.login_throttle_repo.find_locked_until(key).await? {
+            Some(locked_until) => Err(login_locked(locked_until)),
+            None => Ok(()),
+        }
+    }
+
+    /// Counts a failed login against `key`. Returns the end of the lockout if
+    /// this failure reached `max_attempts`.
+    async fn record_login_failure(&self, key: &LoginThrottleKey<'_>, max_attempts: u32) -> Result<Option<DateTime<Utc>>, AppError> {
+        let throttle = self.login_throttle_repo.record_failure(key, LOGIN_FAILURE_MEMORY).await?;
+        if (throttle.failed_attempts as u32) < max_attempts {
+            return Ok(None);
+        }
+        let locked_until = Utc::now() + next_lockout_duration(throttle.lockouts)?;
+        self.login_throttle_repo.lock(key, locked_until).await?;
+        Ok(Some(locked_until))
+    }
+
+    /// Records a failed password or second factor for the account and the client IP.
+    /// Returns the end of the lockout if either is now locked.
+    async fn record_failed_login(&self, account_key: &LoginThrottleKey<'_>, ip_key: Option<&LoginThrottleKey<'_>>, user_id: Option<Uuid>) -> Result<Option<DateTime<Utc>>, AppError> {
+        let ip_locked_until = match ip_key {
+            Some(ip_key) => self.record_login_failure(ip_key, CONFIG.login_max_failed_attempts_per_ip).await?,
+            None => None,
+        };
+        let account_locked_until = self.record_login_failure(account_key, CONFIG.login_max_failed_attempts).await?;
+
+        if let (Some(locked_until), Some(user_id)) = (account_locked_until, user_id) {
+            self.security_event_repo
+                .create(Some(user_id), event_types::ACCOUNT_LOCKED, &format!("Account locked until {} after too many failed logins", locked_until))
+                .await?;
+        }
+
+        Ok(account_locked_until.max(ip_locked_until))
+    }
+
+    /// Records a failed login for the account and the client IP, and returns the error to answer with.
+    async fn fail_login(&self, account_key: &LoginThrottleKey<'_>, ip_key: Option<&LoginThrottleKey<'_>>, user_id: Option<Uuid>) -> Result<AppError, AppError> {
+        Ok(match self.record_failed_login(account_key, ip_key, user_id).await? {
+            Some(locked_until) => login_locked(locked_until),
+            None => AppError::Unauthorized(ErrorCode::InvalidCredentials.into()),
+        })
+    }
+
+    async fn find_enabled_two_factor(&self, user_id: Uuid) -> Result<Option<TwoFactor>, AppError> {
+        Ok(self.two_factor_repo.find_by_user(user_id).await?.filter(|two_factor| two_factor.enabled))
+    }
+
+    /// Creates an access/refresh token pair and stores the refresh token in `family_id`.
+    async fn issue_tokens(&self, user_id: Uuid, family_id: Uuid, client: &ClientInfo) -> Result<TokenResponse, AppError> {
+        let access_jti = Uuid::new_v4();
+        let tokens = create_auth_tokens(&self.jwt_keys, user_id, access_jti)?;
+        let access = AccessTokenRef { jti: access_jti, expires_at: token_expires_at(&tokens.access_token)? };
+        let expires_at = token_expires_at(&tokens.refresh_token)?;
+        self.token_repo.create_in_family(&tokens.refresh_token.token, user_id, expires_at, family_id, &access, client).await?;
+        Ok(tokens)
+    }
+
+    async fn revoke_reused_family(&self, token_doc: &Token) -> Result<(), AppError> {
+        let description = match token_doc.family_id {
+            Some(family_id) => {
+                self.revoked_token_repo.revoke_family(token_doc.user_id, family_id).await?;
+                self.token_repo.delete_family(family_id).await?;
+                format!("Rotated refresh token was reused, token family {} revoked", family_id)
+            }
+            None => {
+                self.token_repo.delete(token_doc.id).await?;
+                "Rotated refresh token was reused".to_string()
+            }
+        };
+        tracing::warn!("User {}: {}", token_doc.user_id, description);
+        self.security_event_repo
+            .create(Some(token_doc.user_id), event_types::REFRESH_TOKEN_REUSE, &description)
+            .await?;
+        Ok(())
+    }
+
+    /// Checks the JWT signature and expiry, then makes sure the token is still
+    /// stored for its owner with the expected type.
+    async fn verify_token(&self, token: &str, token_type: TokenType) -> Result<Token, AppError> {
+        let claims: TokenClaims = self.jwt_keys.decode(token)?;
+        if claims.token_type != token_type.as_str() {
+            return Err(AppError::Unauthorized(ErrorCode::TokenInvalid.into()));
+        }
+
+        let token_doc = self.token_repo.find_by_token(token).await?
+            .filter(|t| t.token_type == token_type && t.user_id == claims.sub && t.expires_at > Utc::now())
+            .ok_or_else(|| AppError::NotFound(ErrorCode::TokenNotFound.into()))?;
+        Ok(token_doc)
     }
 }
 
@@ -66,54 +250,313 @@ impl AuthUsecase for AuthUsecaseImpl {
     async fn register(
         &self,
         data: RegisterSchema,
+        client: ClientInfo,
     ) -> Result<(FilteredUser, TokenResponse), AppError> {
         if self.user_repo.find_by_email(&data.email).await?.is_some() {
-            return Err(AppError::BadRequest("Email already taken".to_string()));
+            return Err(AppError::BadRequest(ErrorCode::EmailTaken.into()));
         }
-        let password_hash = hash(&data.password, DEFAULT_COST)?;
-        let new_user = self.user_repo.create(&data.name, &data.email, &password_hash, Role::User).await?;
-        let tokens = create_auth_tokens(new_user.id)?;
-        
-        let expires_at = Utc::now() + Duration::days(30);
-        self.token_repo.create(&tokens.refresh_token.token, new_user.id, expires_at, TokenType::Refresh).await?;
+        self.password_policy.validate("password", &data.password, &[&data.name, &data.email])?;
+        let password_hash = self.password_hasher.hash(&data.password).await?;
+        let new_user = self.user_repo.create(&data.name, &data.email, &password_hash, DEFAULT_ROLE).await?;
+        let tokens = self.issue_tokens(new_user.id, Uuid::new_v4(), &client).await?;
 
         Ok((new_user.into(), tokens))
     }
 
-    async fn login(&self, data: LoginSchema) -> Result<(FilteredUser, TokenResponse), AppError> {
-        let user = self.user_repo.find_by_email(&data.email).await?
-            .ok_or_else(|| AppError::Unauthorized("Invalid email or password".to_string()))?;
-        let is_valid = verify(&data.password, &user.password)?;
-        if !is_valid {
-            return Err(AppError::Unauthorized("Invalid email or password".to_string()));
+    async fn login(&self, data: LoginSchema, client: ClientInfo) -> Result<LoginOutcome, AppError> {
+        let account_key = LoginThrottleKey::Account(&data.email);
+        let ip_key = client.ip_address.as_deref().map(LoginThrottleKey::Ip);
+        if let Some(ip_key) = &ip_key {
+            self.ensure_login_allowed(ip_key).await?;
+        }
+        self.ensure_login_allowed(&account_key).await?;
+
+        let mut user = match self.user_repo.find_by_email(&data.email).await? {
+            Some(user) if self.password_hasher.verify(&data.password, &user.password).await? => user,
+            user => return Err(self.fail_login(&account_key, ip_key.as_ref(), user.map(|user| user.id)).await?),
+        };
+
+        // Upgrade hashes made with an older algorithm or weaker parameters while the password is known
+        if self.password_hasher.needs_rehash(&user.password) {
+            user.password = self.password_hasher.hash(&data.password).await?;
+            user = self.user_repo.save(&user).await?;
         }
-        let tokens = create_auth_tokens(user.id)?;
-        
-        let expires_at = Utc::now() + Duration::days(30);
-        self.token_repo.create(&tokens.refresh_token.token, user.id, expires_at, TokenType::Refresh).await?;
+
+        if self.find_enabled_two_factor(user.id).await?.is_some() {
+            let mfa_token = create_token(&self.jwt_keys, user.id, Uuid::new_v4(), &CONFIG.jwt_mfa_pending_expires_in, TokenType::MfaPending.as_str(), "")?;
+            let expires_at = token_expires_at(&mfa_token)?;
+            self.token_repo.create(&mfa_token.token, user.id, expires_at, TokenType::MfaPending).await?;
+            // Failures are only forgotten once the second factor is passed as well
+            return Ok(LoginOutcome::MfaRequired(MfaChallenge {
+                mfa_token: mfa_token.token,
+                expires_in: mfa_token.expires_in,
+            }));
+        }
+        self.login_throttle_repo.reset(&account_key).await?;
+
+        // Every login starts a new token family
+        let tokens = self.issue_tokens(user.id, Uuid::new_v4(), &client).await?;
+
+        Ok(LoginOutcome::Authenticated(user.into(), tokens))
+    }
+
+    async fn login_two_factor(&self, data: LoginTwoFactorSchema, client: ClientInfo) -> Result<(FilteredUser, TokenResponse), AppError> {
+        let token_doc = self.verify_token(&data.mfa_token, TokenType::MfaPending).await
+            .map_err(|_| AppError::Unauthorized(ErrorCode::MfaChallengeInvalid.into()))?;
+        let user = self.user_repo.find_by_id(token_doc.user_id).await?
+            .ok_or_else(|| AppError::Unauthorized(ErrorCode::MfaChallengeInvalid.into()))?;
+        let two_factor = self.find_enabled_two_factor(user.id).await?
+            .ok_or_else(|| AppError::Unauthorized(ErrorCode::MfaChallengeInvalid.into()))?;
+
+        let account_key = LoginThrottleKey::Account(&user.email);
+        let ip_key = client.ip_address.as_deref().map(LoginThrottleKey::Ip);
+        if let Some(ip_key) = &ip_key {
+            self.ensure_login_allowed(ip_key).await?;
+        }
+        self.ensure_login_allowed(&account_key).await?;
+
+        if !self.verify_second_factor(&two_factor, &data.code).await? {
+            // Wrong codes count towards the account lockout too, so logging in again doesn't buy more guesses
+            if let Some(locked_until) = self.record_failed_login(&account_key, ip_key.as_ref(), Some(user.id)).await? {
+                self.token_repo.delete(token_doc.id).await?;
+                return Err(login_locked(locked_until));
+            }
+            if self.token_repo.record_failed_attempt(token_doc.id).await? >= MAX_MFA_ATTEMPTS {
+                self.token_repo.delete(token_doc.id).await?;
+                return Err(AppError::Unauthorized(ErrorCode::MfaChallengeExhausted.into()));
+            }
+            return Err(AppError::Unauthorized(ErrorCode::TwoFactorCodeInvalid.into()));
+        }
+
+        self.token_repo.delete(token_doc.id).await?;
+        self.login_throttle_repo.reset(&account_key).await?;
+        let tokens = self.issue_tokens(user.id, Uuid::new_v4(), &client).await?;
 
         Ok((user.into(), tokens))
     }
 
     async fn logout(&self, refresh_token: String) -> Result<(), AppError> {
         let token_doc = self.token_repo.find_by_token(&refresh_token).await?
-            .ok_or_else(|| AppError::NotFound("Token not found".to_string()))?;
-        self.token_repo.delete(token_doc.id).await
+            .ok_or_else(|| AppError::NotFound(ErrorCode::TokenNotFound.into()))?;
+        match token_doc.family_id {
+            Some(family_id) => {
+                self.revoked_token_repo.revoke_family(token_doc.user_id, family_id).await?;
+                self.token_repo.delete_family(family_id).await
+            }
+            None => self.token_repo.delete(token_doc.id).await,
+        }
     }
     
-    async fn refresh_auth(&self, refresh_token: String) -> Result<TokenResponse, AppError> {
-        let token_doc = self.token_repo.find_by_token(&refresh_token).await?
-            .ok_or_else(|| AppError::Unauthorized("Please authenticate".to_string()))?;
+    async fn refresh_auth(&self, refresh_token: String, client: ClientInfo) -> Result<TokenResponse, AppError> {
+        let token_doc = self.token_repo.find_by_token_with_blacklisted(&refresh_token).await?
+            .filter(|t| t.token_type == TokenType::Refresh)
+            .ok_or_else(|| AppError::Unauthorized(ErrorCode::TokenInvalid.into()))?;
+
+        // A consumed token being presented again means it was copied: revoke the whole family
+        if token_doc.blacklisted || !self.token_repo.blacklist(token_doc.id).await? {
+            self.revoke_reused_family(&token_doc).await?;
+            return Err(AppError::Unauthorized(ErrorCode::TokenRevoked.into()));
+        }
+        if token_doc.expires_at <= Utc::now() {
+            return Err(AppError::Unauthorized(ErrorCode::TokenExpired.into()));
+        }
 
         let user = self.user_repo.find_by_id(token_doc.user_id).await?
-            .ok_or_else(|| AppError::Unauthorized("Please authenticate".to_string()))?;
-            
-        self.token_repo.delete(token_doc.id).await?;
+            .ok_or_else(|| AppError::Unauthorized(ErrorCode::TokenInvalid.into()))?;
 
-        let tokens = create_auth_tokens(user.id)?;
-        let expires_at = Utc::now() + Duration::days(30);
-        self.token_repo.create(&tokens.refresh_token.token, user.id, expires_at, TokenType::Refresh).await?;
-        
-        Ok(tokens)
+        // Tokens issued before families existed start one now
+        let family_id = token_doc.family_id.unwrap_or_else(Uuid::new_v4);
+        self.issue_tokens(user.id, family_id, &client).await
+    }
+
+    async fn send_verification_email(&self, user: User, locale: Locale) -> Result<(), AppError> {
+        if user.is_email_verified {
+            return Err(AppError::BadRequest(ErrorCode::EmailAlreadyVerified.into()));
+        }
+        let token = create_token(&self.jwt_keys, user.id, Uuid::new_v4(), &CONFIG.jwt_verify_email_expires_in, TokenType::VerifyEmail.as_str(), "")?;
+        let expires_at = token_expires_at(&token)?;
+
+        // Only the most recently issued link stays valid
+        self.token_repo.delete_user_tokens_by_type(user.id, TokenType::VerifyEmail).await?;
+        self.token_repo.create(&token.token, user.id, expires_at, TokenType::VerifyEmail).await?;
+
+        self.mailer.send(templates::verification_email(&user.email, &token.token, locale)).await
+    }
+
+    async fn verify_email(&self, token: String) -> Result<(), AppError> {
+        let token_doc = self.verify_token(&token, TokenType::VerifyEmail).await
+            .map_err(|_| AppError::Unauthorized(ErrorCode::EmailVerificationFailed.into()))?;
+
+        let mut user = self.user_repo.find_by_id(token_doc.user_id).await?
+            .ok_or_else(|| AppError::Unauthorized(ErrorCode::EmailVerificationFailed.into()))?;
+
+        self.token_repo.delete_user_tokens_by_type(user.id, TokenType::VerifyEmail).await?;
+
+        user.is_email_verified = true;
+        self.user_repo.save(&user).await?;
+        Ok(())
+    }
+
+    async fn forgot_password(&self, email: String, locale: Locale) -> Result<(), AppError> {
+        // Unknown emails get the same response so the endpoint can't be used to probe for accounts
+        let Some(user) = self.user_repo.find_by_email(&email).await? else {
+            return Ok(());
+        };
+
+        let token = create_token(&self.jwt_keys, user.id, Uuid::new_v4(), &CONFIG.jwt_reset_password_expires_in, TokenType::ResetPassword.as_str(), "")?;
+        let expires_at = token_expires_at(&token)?;
+
+        self.token_repo.delete_user_tokens_by_type(user.id, TokenType::ResetPassword).await?;
+        self.token_repo.create(&token.token, user.id, expires_at, TokenType::ResetPassword).await?;
+
+        let locale = user.locale.as_deref().and_then(Locale::parse).unwrap_or(locale);
+
+        // Sent in the background so neither SMTP latency nor SMTP failures reveal that the account exists
+        let mailer = self.mailer.clone();
+        tokio::spawn(async move {
+            if let Err(err) = mailer.send(templates::reset_password_email(&user.email, &token.token, locale)).await {
+                tracing::error!("Failed to send reset password email: {:?}", err);
+            }
+        });
+        Ok(())
+    }
+
+    async fn reset_password(&self, token: String, new_password: String) -> Result<(), AppError> {
+        let token_doc = self.verify_token(&token, TokenType::ResetPassword).await
+            .map_err(|_| AppError::Unauthorized(ErrorCode::PasswordResetFailed.into()))?;
+
+        let user = self.user_repo.find_by_id(token_doc.user_id).await?
+            .ok_or_else(|| AppError::Unauthorized(ErrorCode::PasswordResetFailed.into()))?;
+
+        self.password_policy.validate("password", &new_password, &[&user.name, &user.email])?;
+        let password_hash = self.password_hasher.hash(&new_password).await?;
+        self.user_repo.update_by_id(user.id, None, None, Some(password_hash), None).await?;
+
+        // The token is single-use and every existing session is revoked
+        self.token_repo.delete_user_tokens_by_type(user.id, TokenType::ResetPassword).await?;
+        self.revoked_token_repo.revoke_user_families(user.id, None).await?;
+        self.token_repo.delete_user_tokens_by_type(user.id, TokenType::Refresh).await?;
+        Ok(())
+    }
+
+    async fn change_password(&self, user: User, data: ChangePasswordSchema) -> Result<(), AppError> {
+        let is_valid = self.password_hasher.verify(&data.current_password, &user.password).await?;
+        if !is_valid {
+            return Err(AppError::Unauthorized(ErrorCode::CurrentPasswordIncorrect.into()));
+        }
+        self.password_policy.validate("new_password", &data.new_password, &[&user.name, &user.email])?;
+
+        // Resolve the session to keep before touching anything
+        let keep_token = match &data.refresh_token {
+            Some(refresh_token) => Some(
+                self.token_repo.find_by_token(refresh_token).await?
+                    .filter(|t| t.token_type == TokenType::Refresh && t.user_id == user.id)
+                    .ok_or_else(|| AppError::Unauthorized(ErrorCode::TokenInvalid.into()))?,
+            ),
+            None => None,
+        };
+
+        let password_hash = self.password_hasher.hash(&data.new_password).await?;
+        self.user_repo.update_by_id(user.id, None, None, Some(password_hash), None).await?;
+
+        self.revoked_token_repo
+            .revoke_user_families(user.id, keep_token.as_ref().and_then(|t| t.family_id))
+            .await?;
+        match keep_token {
+            Some(token) => self.token_repo.delete_user_tokens_by_type_except(user.id, TokenType::Refresh, token.id).await,
+            None => self.token_repo.delete_user_tokens_by_type(user.id, TokenType::Refresh).await,
+        }
     }
-}
\ No newline at end of file
+
+    async fn enroll_two_factor(&self, user: User) -> Result<TwoFactorEnrollment, AppError> {
+        if self.find_enabled_two_factor(user.id).await?.is_some() {
+            return Err(AppError::BadRequest(ErrorCode::TwoFactorAlreadyEnabled.into()));
+        }
+
+        let secret = totp::generate_secret();
+        let otpauth_uri = totp::otpauth_uri(&secret, &user.email)?;
+        self.two_factor_repo.upsert_secret(user.id, &secret).await?;
+
+        Ok(TwoFactorEnrollment { secret, otpauth_uri })
+    }
+
+    async fn confirm_two_factor(&self, user: User, code: String) -> Result<RecoveryCodes, AppError> {
+        let two_factor = self.two_factor_repo.find_by_user(user.id).await?
+            .ok_or_else(|| AppError::BadRequest(ErrorCode::TwoFactorNotEnrolled.into()))?;
+        if two_factor.enabled {
+            return Err(AppError::BadRequest(ErrorCode::TwoFactorAlreadyEnabled.into()));
+        }
+
+        let step = totp::verify(&two_factor.secret, &code, None)?
+            .ok_or_else(|| AppError::BadRequest(ErrorCode::TwoFactorCodeInvalid.into()))?;
+
+        let recovery_codes = totp::generate_recovery_codes();
+        let normalized: Vec<String> = recovery_codes.iter().map(|code| totp::normalize_recovery_code(code)).collect();
+        self.two_factor_repo.enable(user.id, step, &normalized).await?;
+        self.security_event_repo
+            .create(Some(user.id), event_types::TWO_FACTOR_ENABLED, "Two-factor authentication enabled")
+            .await?;
+
+        Ok(RecoveryCodes { recovery_codes })
+    }
+
+    async fn disable_two_factor(&self, user: User, code: String) -> Result<(), AppError> {
+        let two_factor = self.find_enabled_two_factor(user.id).await?
+            .ok_or_else(|| AppError::BadRequest(ErrorCode::TwoFactorNotEnabled.into()))?;
+
+        // A stolen access token alone must not be enough to turn 2FA off
+        if !self.verify_second_factor(&two_factor, &code).await? {
+            return Err(AppError::Unauthorized(ErrorCode::TwoFactorCodeInvalid.into()));
+        }
+
+        self.two_factor_repo.delete(user.id).await?;
+        self.security_event_repo
+            .create(Some(user.id), event_types::TWO_FACTOR_DISABLED, "Two-factor authentication disabled")
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn first_lockout_lasts_the_base_duration() {
+        assert_eq!(lockout_duration(MINUTE, HOUR, 0), MINUTE);
+    }
+
+    #[test]
+    fn doubles_for_each_lockout_in_a_row() {
+        assert_eq!(lockout_duration(MINUTE, HOUR, 1), 2 * MINUTE);
+        assert_eq!(lockout_duration(MINUTE, HOUR, 5), 32 * MINUTE);
+    }
+
+    #[test]
+    fn is_capped_at_the_maximum() {
+        assert_eq!(lockout_duration(MINUTE, HOUR, 6), HOUR);
+        assert_eq!(lockout_duration(MINUTE, HOUR, 31), HOUR);
+    }
+
+    #[test]
+    fn large_exponents_saturate_instead_of_overflowing() {
+        assert_eq!(lockout_duration(MINUTE, HOUR, 32), HOUR);
+        assert_eq!(lockout_duration(MINUTE, HOUR, i32::MAX), HOUR);
+        assert_eq!(lockout_duration(MINUTE, Duration::MAX, 64), MINUTE * u32::MAX);
+        assert_eq!(lockout_duration(Duration::MAX, Duration::MAX, 1), Duration::MAX);
+    }
+
+    #[test]
+    fn negative_counts_are_treated_as_none() {
+        assert_eq!(lockout_duration(MINUTE, HOUR, -3), MINUTE);
+    }
+
+    #[test]
+    fn maximum_below_the_base_wins() {
+        assert_eq!(lockout_duration(HOUR, MINUTE, 0), MINUTE);
+    }
+}
@@ -1,2 +1,5 @@
 pub mod auth_usecase;
-pub mod user_usecase;
\ No newline at end of file
+pub mod user_usecase;
+pub mod role_usecase;
+pub mod session_usecase;
+pub mod api_key_usecase;
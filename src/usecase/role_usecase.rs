@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    domain::role_model::{Permission, Role},
+    error::{AppError, ErrorCode},
+    repository::role_repository::RoleRepository,
+};
+
+/// Built-in role that always keeps every permission, so admins can't lock themselves out.
+const ADMIN_ROLE: &str = "admin";
+
+#[async_trait]
+pub trait RoleUsecase: Send + Sync {
+    async fn get_roles(&self) -> Result<Vec<Role>, AppError>;
+    async fn get_role_by_id(&self, id: Uuid) -> Result<Role, AppError>;
+    async fn create_role(&self, name: &str, description: &str, permissions: Vec<String>) -> Result<Role, AppError>;
+    async fn update_role(&self, id: Uuid, description: Option<String>, permissions: Option<Vec<String>>) -> Result<Role, AppError>;
+    async fn delete_role(&self, id: Uuid) -> Result<(), AppError>;
+    async fn get_permissions(&self) -> Result<Vec<Permission>, AppError>;
+}
+
+pub struct RoleUsecaseImpl {
+    role_repo: Arc<dyn RoleRepository>,
+}
+
+impl RoleUsecaseImpl {
+    pub fn new(role_repo: Arc<dyn RoleRepository>) -> Self {
+        Self { role_repo }
+    }
+
+    async fn ensure_permissions_exist(&self, permissions: &[String]) -> Result<(), AppError> {
+        let known = self.role_repo.find_all_permissions().await?;
+        if let Some(unknown) = permissions.iter().find(|p| !known.iter().any(|k| &k.name == *p)) {
+            return Err(AppError::BadRequest(ErrorCode::UnknownPermission.with("permission", unknown)));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RoleUsecase for RoleUsecaseImpl {
+    async fn get_roles(&self) -> Result<Vec<Role>, AppError> {
+        self.role_repo.find_all().await
+    }
+
+    async fn get_role_by_id(&self, id: Uuid) -> Result<Role, AppError> {
+        self.role_repo.find_by_id(id).await?.ok_or_else(|| AppError::NotFound(ErrorCode::RoleNotFound.into()))
+    }
+
+    async fn create_role(&self, name: &str, description: &str, permissions: Vec<String>) -> Result<Role, AppError> {
+        if self.role_repo.find_by_name(name).await?.is_some() {
+            return Err(AppError::BadRequest(ErrorCode::RoleExists.into()));
+        }
+        self.ensure_permissions_exist(&permissions).await?;
+        self.role_repo.create(name, description, &permissions).await
+    }
+
+    async fn update_role(&self, id: Uuid, description: Option<String>, permissions: Option<Vec<String>>) -> Result<Role, AppError> {
+        let role = self.get_role_by_id(id).await?;
+        if let Some(permissions) = &permissions {
+            if role.name == ADMIN_ROLE {
+                return Err(AppError::BadRequest(ErrorCode::RoleProtected.into()));
+            }
+            self.ensure_permissions_exist(permissions).await?;
+        }
+        self.role_repo.update_by_id(id, description, permissions).await
+    }
+
+    async fn delete_role(&self, id: Uuid) -> Result<(), AppError> {
+        let role = self.get_role_by_id(id).await?;
+        if role.is_system {
+            return Err(AppError::BadRequest(ErrorCode::RoleProtected.into()));
+        }
+        if self.role_repo.count_users(&role.name).await? > 0 {
+            return Err(AppError::BadRequest(ErrorCode::RoleInUse.into()));
+        }
+        self.role_repo.delete_by_id(id).await
+    }
+
+    async fn get_permissions(&self) -> Result<Vec<Permission>, AppError> {
+        self.role_repo.find_all_permissions().await
+    }
+}
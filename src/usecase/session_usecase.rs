@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    domain::{session_model::Session, token_model::TokenType},
+    error::{AppError, ErrorCode},
+    repository::{
+        revoked_token_repository::RevokedTokenRepository,
+        token_repository::TokenRepository,
+        user_repository::UserRepository,
+    },
+};
+
+#[async_trait]
+pub trait SessionUsecase: Send + Sync {
+    async fn get_sessions(&self, user_id: Uuid) -> Result<Vec<Session>, AppError>;
+    async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> Result<(), AppError>;
+    async fn revoke_all_sessions(&self, user_id: Uuid) -> Result<(), AppError>;
+}
+
+pub struct SessionUsecaseImpl {
+    user_repo: Arc<dyn UserRepository>,
+    token_repo: Arc<dyn TokenRepository>,
+    revoked_token_repo: Arc<dyn RevokedTokenRepository>,
+}
+
+impl SessionUsecaseImpl {
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        revoked_token_repo: Arc<dyn RevokedTokenRepository>,
+    ) -> Self {
+        Self { user_repo, token_repo, revoked_token_repo }
+    }
+
+    async fn ensure_user_exists(&self, user_id: Uuid) -> Result<(), AppError> {
+        self.user_repo.find_by_id(user_id).await?
+            .ok_or_else(|| AppError::NotFound(ErrorCode::UserNotFound.into()))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SessionUsecase for SessionUsecaseImpl {
+    async fn get_sessions(&self, user_id: Uuid) -> Result<Vec<Session>, AppError> {
+        self.ensure_user_exists(user_id).await?;
+        self.token_repo.find_sessions_by_user(user_id).await
+    }
+
+    async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> Result<(), AppError> {
+        self.revoked_token_repo.revoke_family(user_id, session_id).await?;
+        if !self.token_repo.delete_user_family(user_id, session_id).await? {
+            return Err(AppError::NotFound(ErrorCode::SessionNotFound.into()));
+        }
+        Ok(())
+    }
+
+    async fn revoke_all_sessions(&self, user_id: Uuid) -> Result<(), AppError> {
+        self.ensure_user_exists(user_id).await?;
+        self.revoked_token_repo.revoke_user_families(user_id, None).await?;
+        self.token_repo.delete_user_tokens_by_type(user_id, TokenType::Refresh).await
+    }
+}
@@ -1,40 +1,91 @@
 use async_trait::async_trait;
 use std::sync::Arc;
 use uuid::Uuid;
-use bcrypt::hash;
 
 use crate::{
-    domain::user_model::{FilteredUser, Role, User},
-    error::AppError,
-    repository::user_repository::{PaginatedResult, UserRepository, UserQueryOptions},
+    domain::{role_model::permissions, token_model::TokenType, user_model::{FilteredUser, User}},
+    error::{AppError, ErrorCode},
+    i18n::Locale,
+    policy,
+    password::{PasswordHasher, PasswordPolicy},
+    repository::{
+        login_throttle_repository::{LoginThrottleKey, LoginThrottleRepository},
+        revoked_token_repository::RevokedTokenRepository,
+        role_repository::RoleRepository,
+        security_event_repository::{event_types, SecurityEventRepository},
+        token_repository::TokenRepository,
+        user_repository::{PaginatedResult, UserRepository, UserQueryOptions},
+    },
 };
 
 #[async_trait]
 pub trait UserUsecase: Send + Sync {
-    async fn create_user(&self, name: &str, email: &str, password: &str, role: Role) -> Result<FilteredUser, AppError>;
+    /// `actor` can only hand out roles it could also define, see `ensure_can_assign_role`.
+    async fn create_user(&self, actor: &User, name: &str, email: &str, password: &str, role: &str) -> Result<FilteredUser, AppError>;
     async fn get_users(&self, options: UserQueryOptions) -> Result<PaginatedResult<FilteredUser>, AppError>;
     async fn get_user_by_id(&self, id: Uuid) -> Result<FilteredUser, AppError>;
-    async fn update_user(&self, id: Uuid, name: Option<String>, email: Option<String>, password: Option<String>) -> Result<FilteredUser, AppError>;
+    /// Changing the login or role of an account requires `actor` to hold every
+    /// permission the account has. Nobody can change their own role here.
+    async fn update_user(&self, actor: &User, id: Uuid, name: Option<String>, email: Option<String>, password: Option<String>, role: Option<String>) -> Result<FilteredUser, AppError>;
     async fn delete_user(&self, id: Uuid) -> Result<(), AppError>;
+    /// Ends a login lockout of the user before it expires.
+    async fn unlock_user(&self, id: Uuid) -> Result<(), AppError>;
+    async fn update_profile(&self, user: User, name: Option<String>, email: Option<String>, password: Option<String>, current_password: Option<String>, locale: Option<String>) -> Result<FilteredUser, AppError>;
 }
 
 pub struct UserUsecaseImpl {
     user_repo: Arc<dyn UserRepository>,
+    role_repo: Arc<dyn RoleRepository>,
+    token_repo: Arc<dyn TokenRepository>,
+    revoked_token_repo: Arc<dyn RevokedTokenRepository>,
+    login_throttle_repo: Arc<dyn LoginThrottleRepository>,
+    security_event_repo: Arc<dyn SecurityEventRepository>,
+    password_hasher: Arc<dyn PasswordHasher>,
+    password_policy: Arc<PasswordPolicy>,
 }
 
 impl UserUsecaseImpl {
-    pub fn new(user_repo: Arc<dyn UserRepository>) -> Self {
-        Self { user_repo }
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repo: Arc<dyn UserRepository>,
+        role_repo: Arc<dyn RoleRepository>,
+        token_repo: Arc<dyn TokenRepository>,
+        revoked_token_repo: Arc<dyn RevokedTokenRepository>,
+        login_throttle_repo: Arc<dyn LoginThrottleRepository>,
+        security_event_repo: Arc<dyn SecurityEventRepository>,
+        password_hasher: Arc<dyn PasswordHasher>,
+        password_policy: Arc<PasswordPolicy>,
+    ) -> Self {
+        Self { user_repo, role_repo, token_repo, revoked_token_repo, login_throttle_repo, security_event_repo, password_hasher, password_policy }
+    }
+
+    /// Signs the user out everywhere after a password change, like `AuthUsecase::change_password`.
+    async fn revoke_sessions(&self, user_id: Uuid) -> Result<(), AppError> {
+        self.revoked_token_repo.revoke_user_families(user_id, None).await?;
+        self.token_repo.delete_user_tokens_by_type(user_id, TokenType::Refresh).await
+    }
+
+    /// The role exists and `actor` may grant it: either they manage roles, or
+    /// they already hold every permission of the role.
+    async fn ensure_can_assign_role(&self, actor: &User, role: &str) -> Result<(), AppError> {
+        let role = self.role_repo.find_by_name(role).await?
+            .ok_or_else(|| AppError::BadRequest(ErrorCode::RoleNotFound.into()))?;
+        if !policy::has_permission(actor, permissions::ROLES_WRITE) && !policy::holds_all(actor, &role.permissions) {
+            return Err(AppError::Forbidden(ErrorCode::PermissionDenied.into()));
+        }
+        Ok(())
     }
 }
 
 #[async_trait]
 impl UserUsecase for UserUsecaseImpl {
-    async fn create_user(&self, name: &str, email: &str, password: &str, role: Role) -> Result<FilteredUser, AppError> {
+    async fn create_user(&self, actor: &User, name: &str, email: &str, password: &str, role: &str) -> Result<FilteredUser, AppError> {
+        self.ensure_can_assign_role(actor, role).await?;
         if self.user_repo.find_by_email(email).await?.is_some() {
-            return Err(AppError::BadRequest("Email already taken".to_string()));
+            return Err(AppError::BadRequest(ErrorCode::EmailTaken.into()));
         }
-        let password_hash = hash(password, 10)?;
+        self.password_policy.validate("password", password, &[name, email])?;
+        let password_hash = self.password_hasher.hash(password).await?;
         let new_user = self.user_repo.create(name, email, &password_hash, role).await?;
         Ok(new_user.into())
     }
@@ -51,17 +102,101 @@ impl UserUsecase for UserUsecaseImpl {
     }
 
     async fn get_user_by_id(&self, id: Uuid) -> Result<FilteredUser, AppError> {
-        let user = self.user_repo.find_by_id(id).await?.ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        let user = self.user_repo.find_by_id(id).await?.ok_or_else(|| AppError::NotFound(ErrorCode::UserNotFound.into()))?;
         Ok(user.into())
     }
 
-    async fn update_user(&self, id: Uuid, name: Option<String>, email: Option<String>, password: Option<String>) -> Result<FilteredUser, AppError> {
-        let password_hash = if let Some(p) = password { Some(hash(p, 10)?) } else { None };
-        let updated_user = self.user_repo.update_by_id(id, name, email, password_hash).await?;
+    async fn update_user(&self, actor: &User, id: Uuid, name: Option<String>, email: Option<String>, password: Option<String>, role: Option<String>) -> Result<FilteredUser, AppError> {
+        let user = self.user_repo.find_by_id(id).await?
+            .ok_or_else(|| AppError::NotFound(ErrorCode::UserNotFound.into()))?;
+        let role = role.filter(|role| *role != user.role);
+
+        if let Some(role) = &role {
+            if actor.id == user.id {
+                return Err(AppError::Forbidden(ErrorCode::OwnRoleChange.into()));
+            }
+            self.ensure_can_assign_role(actor, role).await?;
+        }
+        // Whoever can set the login of an account can sign in as it
+        let takes_over = role.is_some() || password.is_some() || email.as_ref().is_some_and(|email| *email != user.email);
+        if takes_over && !policy::holds_all(actor, &user.permissions) {
+            return Err(AppError::Forbidden(ErrorCode::PermissionDenied.into()));
+        }
+
+        let password_changed = password.is_some();
+        let password_hash = match password {
+            Some(password) => {
+                let name = name.as_deref().unwrap_or(&user.name);
+                let email = email.as_deref().unwrap_or(&user.email);
+                self.password_policy.validate("password", &password, &[name, email])?;
+                Some(self.password_hasher.hash(&password).await?)
+            }
+            None => None,
+        };
+        let updated_user = self.user_repo.update_by_id(id, name, email, password_hash, role).await?;
+        if password_changed {
+            self.revoke_sessions(updated_user.id).await?;
+        }
         Ok(updated_user.into())
     }
     
     async fn delete_user(&self, id: Uuid) -> Result<(), AppError> {
         self.user_repo.delete_by_id(id).await
     }
+
+    async fn unlock_user(&self, id: Uuid) -> Result<(), AppError> {
+        let user = self.user_repo.find_by_id(id).await?
+            .ok_or_else(|| AppError::NotFound(ErrorCode::UserNotFound.into()))?;
+        if self.login_throttle_repo.reset(&LoginThrottleKey::Account(&user.email)).await? {
+            self.security_event_repo
+                .create(Some(user.id), event_types::ACCOUNT_UNLOCKED, "Login lockout cleared by an administrator")
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn update_profile(&self, mut user: User, name: Option<String>, email: Option<String>, password: Option<String>, current_password: Option<String>, locale: Option<String>) -> Result<FilteredUser, AppError> {
+        let new_email = email.filter(|email| *email != user.email);
+        // A stolen access token alone must not be enough to take over the login
+        if new_email.is_some() || password.is_some() {
+            let current_password = current_password
+                .ok_or_else(|| AppError::BadRequest(ErrorCode::CurrentPasswordRequired.into()))?;
+            if !self.password_hasher.verify(&current_password, &user.password).await? {
+                return Err(AppError::Unauthorized(ErrorCode::CurrentPasswordIncorrect.into()));
+            }
+        }
+
+        let old_email = user.email.clone();
+        if let Some(email) = &new_email {
+            if self.user_repo.find_by_email(email).await?.is_some() {
+                return Err(AppError::BadRequest(ErrorCode::EmailTaken.into()));
+            }
+            // A new address has to be verified again. Saving it also drops the
+            // verification and reset links sent to the old one (see the migration)
+            user.email = email.clone();
+            user.is_email_verified = false;
+        }
+        if let Some(name) = name {
+            user.name = name;
+        }
+        if let Some(locale) = locale.as_deref().and_then(Locale::parse) {
+            user.locale = Some(locale.as_str().to_string());
+        }
+        let password_changed = password.is_some();
+        if let Some(password) = password {
+            self.password_policy.validate("password", &password, &[&user.name, &user.email])?;
+            user.password = self.password_hasher.hash(&password).await?;
+        }
+        let updated_user = self.user_repo.save(&user).await?;
+
+        if new_email.is_some() {
+            self.security_event_repo
+                .create(Some(user.id), event_types::EMAIL_CHANGED, &format!("Email changed from {} to {}", old_email, updated_user.email))
+                .await?;
+        }
+        if password_changed {
+            self.revoke_sessions(updated_user.id).await?;
+        }
+        Ok(updated_user.into())
+    }
 }
\ No newline at end of file